@@ -1,3 +1,31 @@
+use std::fs;
+use std::process::Command;
+
 fn main() {
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha());
+    println!("cargo:rustc-env=RDEV_VERSION={}", rdev_version());
+    println!("cargo:rustc-env=BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string()));
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
     tauri_build::build()
 }
+
+/// 取得目前的 git commit (沒有 git 時為 unknown)
+fn git_sha() -> String {
+    Command::new("git").args(["rev-parse", "--short", "HEAD"]).output().ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|sha| sha.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 從 Cargo.lock 取得 rdev 的版本 (找不到時為 unknown)
+fn rdev_version() -> String {
+    let lock = fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let mut lines = lock.lines().skip_while(|line| line.trim() != "name = \"rdev\"");
+    lines.nth(1)
+        .and_then(|line| line.trim().strip_prefix("version = "))
+        .map(|version| version.trim_matches('"').to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,95 @@
+//! 錄製 / 回放使用的時間來源 (測試時可換成不需真的等待的時鐘)
+
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::lock_or_recover;
+
+/// 時間來源
+pub trait Clock: Send + Sync {
+
+    /// 取得目前時間
+    /// # 返回值
+    /// - SystemTime
+    fn now(&self) -> SystemTime;
+
+    /// 取得單調時間 (從固定起點開始計算，不受系統時間調整的影響，只用來計算間隔；預設以 Instant 計算)
+    /// # 返回值
+    /// - Duration
+    fn monotonic(&self) -> Duration {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed()
+    }
+
+    /// 等待一段時間
+    /// # 參數
+    /// - `duration` - Duration
+    fn sleep(&self, duration: Duration);
+}
+
+/// 系統時鐘 (預設)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+
+    fn now(&self) -> SystemTime { SystemTime::now() }
+
+    fn sleep(&self, duration: Duration) { thread::sleep(duration) }
+}
+
+/// 模擬的時鐘 (sleep 不會真的等待，只把時間往後推並記下等待的時間；複製後共用同一個時間)
+/// - 系統時間與單調時間分開記錄，`set_now` 只調整系統時間，可模擬錄製期間系統時間被調整
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug)]
+struct MockState {
+    now: SystemTime,
+    monotonic: Duration,
+    sleeps: Vec<Duration>,
+}
+
+impl MockClock {
+
+    /// 建立從指定時間開始的時鐘
+    /// # 參數
+    /// - `now` - SystemTime 開始時間
+    pub fn new(now: SystemTime) -> Self { MockClock { state: Arc::new(Mutex::new(MockState { now, monotonic: Duration::ZERO, sleeps: Vec::new() })) } }
+
+    /// 讓時間往後推
+    /// # 參數
+    /// - `duration` - Duration
+    pub fn advance(&self, duration: Duration) {
+        let mut state = lock_or_recover(&self.state);
+        state.now += duration;
+        state.monotonic += duration;
+    }
+
+    /// 直接設定目前的系統時間 (模擬系統時間被調整，單調時間不變)
+    /// # 參數
+    /// - `now` - SystemTime
+    pub fn set_now(&self, now: SystemTime) { lock_or_recover(&self.state).now = now; }
+
+    /// 取得依序呼叫 sleep 的等待時間
+    /// # 返回值
+    /// - Vec<Duration>
+    pub fn sleeps(&self) -> Vec<Duration> { lock_or_recover(&self.state).sleeps.clone() }
+}
+
+impl Clock for MockClock {
+
+    fn now(&self) -> SystemTime { lock_or_recover(&self.state).now }
+
+    fn monotonic(&self) -> Duration { lock_or_recover(&self.state).monotonic }
+
+    fn sleep(&self, duration: Duration) {
+        let mut state = lock_or_recover(&self.state);
+        state.now += duration;
+        state.monotonic += duration;
+        state.sleeps.push(duration);
+    }
+}
@@ -0,0 +1,158 @@
+//! 把錄製的滑鼠路徑畫在主螢幕截圖上，輸出成 GIF 動畫 (需啟用 `demo-gif` feature)
+//! - 背景只擷取一次 (匯出當下的主螢幕畫面)，路徑以錄製時的間隔播放，點擊時在游標位置閃一下圓圈
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::time::Duration;
+use image::{Delay, Frame, Rgba, RgbaImage};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{resize, FilterType};
+use rdev::EventType::{ButtonPress, MouseMove};
+
+use crate::recorder::StoredEvent;
+use crate::screen::capture_primary_image;
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);   // 畫格之間最短的間隔 (20 fps)
+const LAST_FRAME_HOLD: Duration = Duration::from_secs(1);     // 最後一格停留的時間
+const MIN_FRAME_DELAY_MS: u32 = 20;                           // 瀏覽器會把更短的延遲當成 100ms
+const TRAIL_COLOR: Rgba<u8> = Rgba([255, 64, 64, 255]);
+const CURSOR_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+const CLICK_COLOR: Rgba<u8> = Rgba([255, 200, 0, 255]);
+
+/// 匯出滑鼠路徑的 GIF 動畫
+/// # 參數
+/// - `events` - &[StoredEvent] 事件 (座標為像素，停用的事件會略過)
+/// - `display_size` - (f64, f64) 主螢幕的大小 (與事件的座標相同單位)
+/// - `path` - &str 檔案路徑
+/// - `width` - Option<u32> 輸出的寬度 (高度依比例計算，None 表示與截圖相同)
+/// # 返回值
+/// - Result<usize, String> - 輸出的畫格數量，沒有滑鼠移動事件時返回錯誤
+pub fn export_demo_gif(events: &[StoredEvent], display_size: (f64, f64), path: &str, width: Option<u32>) -> Result<usize, String> {
+
+    let points = mouse_points(events);
+    if points.is_empty() { return Err("錄製內容沒有滑鼠移動事件".to_string()); }
+
+    let mut background = capture_primary_image()?;
+
+    if let Some(width) = width.filter(|width| *width > 0 && *width != background.width()) {
+        let height = ((background.height() as f64 * width as f64 / background.width() as f64).round() as u32).max(1);
+        background = resize(&background, width, height, FilterType::Triangle);
+    }
+
+    let scale = background.width() as f64 / display_size.0;
+    let file = File::create(path).map_err(|error| error.to_string())?;
+    let mut encoder = GifEncoder::new(BufWriter::new(file));
+    encoder.set_repeat(Repeat::Infinite).map_err(|error| error.to_string())?;
+
+    let mut trail = background;
+    let mut pending: Option<(RgbaImage, Duration)> = None;
+    let mut frame_count = 0;
+
+    for (index, (offset, x, y, is_click)) in points.iter().enumerate() {
+
+        let (x, y) = ((x * scale).round() as i64, (y * scale).round() as i64);
+        draw_dot(&mut trail, x, y, 1, TRAIL_COLOR);
+
+        let is_due = pending.as_ref().is_none_or(|(_, last)| *offset >= *last + FRAME_INTERVAL);
+        if !is_due && !is_click && index + 1 < points.len() { continue; }
+
+        let mut frame = trail.clone();
+        if *is_click { draw_ring(&mut frame, x, y, 12, CLICK_COLOR); }
+        draw_dot(&mut frame, x, y, 4, CURSOR_COLOR);
+
+        if let Some((previous, last)) = pending.replace((frame, *offset)) {
+            encode_frame(&mut encoder, previous, offset.saturating_sub(last))?;
+            frame_count += 1;
+        }
+    }
+
+    if let Some((last, _)) = pending {
+        encode_frame(&mut encoder, last, LAST_FRAME_HOLD)?;
+        frame_count += 1;
+    }
+
+    Ok(frame_count)
+}
+
+/// 取出滑鼠的位置與點擊 (依錄製時的間隔計算時間)
+/// # 參數
+/// - `events` - &[StoredEvent]
+/// # 返回值
+/// - Vec<(Duration, f64, f64, bool)> - (距離第一個事件的時間, x, y, 是否為點擊)，第一次移動之前的點擊沒有位置會略過
+fn mouse_points(events: &[StoredEvent]) -> Vec<(Duration, f64, f64, bool)> {
+
+    let mut points = Vec::new();
+    let mut position: Option<(f64, f64)> = None;
+    let mut previous: Option<&StoredEvent> = None;
+    let mut offset = Duration::ZERO;
+
+    for event in events.iter().filter(|event| event.enabled) {
+
+        if let Some(previous) = previous { offset += event.gap_since(previous); }
+        previous = Some(event);
+
+        match event.event_type {
+            MouseMove { x, y } => { position = Some((x, y)); points.push((offset, x, y, false)); }
+            ButtonPress(_) => { if let Some((x, y)) = position { points.push((offset, x, y, true)); } }
+            _ => {}
+        }
+    }
+
+    points
+}
+
+/// 寫入一格畫面
+/// # 參數
+/// - `encoder` - &mut GifEncoder<W>
+/// - `image` - RgbaImage
+/// - `delay` - Duration 停留的時間
+/// # 返回值
+/// - Result<(), String>
+fn encode_frame<W: std::io::Write>(encoder: &mut GifEncoder<W>, image: RgbaImage, delay: Duration) -> Result<(), String> {
+    let delay_ms = (delay.as_millis().min(u32::MAX as u128) as u32).max(MIN_FRAME_DELAY_MS);
+    encoder.encode_frame(Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))).map_err(|error| error.to_string())
+}
+
+/// 畫實心圓點 (超出畫面的部分略過)
+/// # 參數
+/// - `image` - &mut RgbaImage
+/// - `x` - i64 圓心 x
+/// - `y` - i64 圓心 y
+/// - `radius` - i64 半徑
+/// - `color` - Rgba<u8>
+fn draw_dot(image: &mut RgbaImage, x: i64, y: i64, radius: i64, color: Rgba<u8>) {
+    draw_circle(image, x, y, |distance| distance <= radius * radius, radius, color);
+}
+
+/// 畫圓圈 (寬 2 像素，超出畫面的部分略過)
+/// # 參數
+/// - `image` - &mut RgbaImage
+/// - `x` - i64 圓心 x
+/// - `y` - i64 圓心 y
+/// - `radius` - i64 半徑
+/// - `color` - Rgba<u8>
+fn draw_ring(image: &mut RgbaImage, x: i64, y: i64, radius: i64, color: Rgba<u8>) {
+    let inner = (radius - 2).max(0);
+    draw_circle(image, x, y, |distance| distance <= radius * radius && distance >= inner * inner, radius, color);
+}
+
+/// 在圓心周圍符合條件的像素上色
+/// # 參數
+/// - `image` - &mut RgbaImage
+/// - `x` - i64 圓心 x
+/// - `y` - i64 圓心 y
+/// - `is_inside` - impl Fn(i64) -> bool 依與圓心距離的平方判斷是否上色
+/// - `radius` - i64 檢查的範圍
+/// - `color` - Rgba<u8>
+fn draw_circle(image: &mut RgbaImage, x: i64, y: i64, is_inside: impl Fn(i64) -> bool, radius: i64, color: Rgba<u8>) {
+
+    let (width, height) = (image.width() as i64, image.height() as i64);
+
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let (px, py) = (x + dx, y + dy);
+            if px < 0 || py < 0 || px >= width || py >= height || !is_inside(dx * dx + dy * dy) { continue; }
+            image.put_pixel(px as u32, py as u32, color);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! 取得目前在前景的應用程式 (錄製時記錄焦點變化用)
+//! - macOS：以 osascript 查詢 System Events (需允許「自動化」權限)
+//! - Linux：以 xdotool 查詢目前視窗的名稱 (需安裝 xdotool，只支援 X11)
+//! - Windows 與 Wayland：尚未支援，不會記錄焦點變化
+
+use std::process::Command;
+
+/// 取得目前在前景的應用程式名稱
+/// # 返回值
+/// - Option<String> - 無法取得時返回 None
+pub fn frontmost_app() -> Option<String> {
+
+    let output = frontmost_app_command()?.output().ok().filter(|output| output.status.success())?;
+    let name = String::from_utf8(output.stdout).ok()?.trim().to_string();
+
+    if name.is_empty() { None } else { Some(name) }
+}
+
+#[cfg(target_os = "macos")]
+fn frontmost_app_command() -> Option<Command> {
+    let mut command = Command::new("osascript");
+    command.args(["-e", "tell application \"System Events\" to get name of first application process whose frontmost is true"]);
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn frontmost_app_command() -> Option<Command> {
+    let mut command = Command::new("xdotool");
+    command.args(["getactivewindow", "getwindowname"]);
+    Some(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn frontmost_app_command() -> Option<Command> {
+    None
+}
@@ -0,0 +1,237 @@
+//! 本機 HTTP 控制介面 (需啟用 `http-api` feature)
+//! - 只綁定 127.0.0.1，可另外設定 token (以 `Authorization: Bearer <token>` 傳入)
+//! - 拒絕帶有 `Origin` 或非本機 `Host` 的請求，避免瀏覽器裡的網頁 (跨站請求或 DNS rebinding) 控制錄製與回放
+//! - POST /start、/stop、/play、/status、/events，與 Tauri 指令共用 recorder 的功能
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::io::ErrorKind;
+use std::thread::{spawn, sleep};
+use std::time::Duration;
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+
+use crate::lock_or_recover;
+use crate::recorder;
+
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+// 用於存儲 HTTP 伺服器停止訊號的全局變數
+lazy_static! {
+    static ref HTTP_SERVER: Arc<Mutex<Option<Arc<AtomicBool>>>> = Arc::new(Mutex::new(None));
+}
+
+/// 啟動 HTTP 控制介面 (新執行緒)
+/// # 參數
+/// - `port` - u16 連接埠 (0 表示由系統分配)
+/// - `token` - Option<String> 驗證用的 token
+/// # 返回值
+/// - Result<u16, String> - 實際使用的連接埠
+pub fn start(port: u16, token: Option<String>) -> Result<u16, String> {
+
+    let mut server = lock_or_recover(&HTTP_SERVER);
+    if server.is_some() { return Err("HTTP 控制介面已在執行".to_string()); }
+
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|error| error.to_string())?;
+    let port = listener.local_addr().map_err(|error| error.to_string())?.port();
+    listener.set_nonblocking(true).map_err(|error| error.to_string())?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    *server = Some(stop.clone());
+
+    spawn(move || {
+
+        while !stop.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => { handle_connection(stream, token.as_deref()); }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => { sleep(Duration::from_millis(50)); }
+                Err(error) => { println!("[Error] HTTP 連線失敗: {:?}", error); sleep(Duration::from_millis(50)); }
+            }
+        }
+    });
+
+    Ok(port)
+}
+
+/// 停止 HTTP 控制介面
+/// # 返回值
+/// - bool - 原本有在執行時返回 true
+pub fn stop() -> bool {
+
+    match lock_or_recover(&HTTP_SERVER).take() {
+        Some(stop) => { stop.store(true, Ordering::SeqCst); true }
+        None => false,
+    }
+}
+
+/// 處理單一連線
+/// # 參數
+/// - `stream` - TcpStream
+/// - `token` - Option<&str> 驗證用的 token
+fn handle_connection(mut stream: TcpStream, token: Option<&str>) {
+
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+
+    let (status, body) = match read_request(&mut stream) {
+        Some(request) => route(&request, token),
+        None => (400, json!({ "error": "bad request" })),
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, status_text(status), body.len(), body
+    );
+
+    if let Err(error) = stream.write_all(response.as_bytes()) { println!("[Error] HTTP 回應失敗: {:?}", error); }
+}
+
+/// 讀取請求的標頭 (不處理 body)
+/// # 參數
+/// - `stream` - &mut TcpStream
+/// # 返回值
+/// - Option<String> - 請求行與標頭
+fn read_request(stream: &mut TcpStream) -> Option<String> {
+
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        if buffer.len() > MAX_REQUEST_SIZE { return None; }
+        let count = stream.read(&mut chunk).ok()?;
+        if count == 0 { break; }
+        buffer.extend_from_slice(&chunk[..count]);
+    }
+
+    String::from_utf8(buffer).ok()
+}
+
+/// 依路徑執行對應的功能
+/// # 參數
+/// - `request` - &str 請求行與標頭
+/// - `token` - Option<&str> 驗證用的 token
+/// # 返回值
+/// - (u16, Value) - HTTP 狀態碼與回應內容
+fn route(request: &str, token: Option<&str>) -> (u16, Value) {
+
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or_default();
+
+    let headers: Vec<(&str, &str)> = lines.filter_map(|line| line.split_once(':')).map(|(name, value)| (name.trim(), value.trim())).collect();
+    let header = |target: &str| headers.iter().find(|(name, _)| name.eq_ignore_ascii_case(target)).map(|(_, value)| *value);
+
+    if !is_local_request(header("origin"), header("host")) { return (403, json!({ "error": "forbidden" })); }
+
+    if let Some(token) = token {
+        if !is_authorized(header("authorization"), token) { return (401, json!({ "error": "unauthorized" })); }
+    }
+
+    if method != "POST" { return (405, json!({ "error": "method not allowed" })); }
+
+    match path {
+        "/start" => (200, json!({ "recording": recorder::record() })),
+        "/stop" => (200, json!({ "recording": recorder::stop(false) })),
+        "/play" => (200, json!({ "playbackId": recorder::spawn_playback() })),
+        "/status" => (200, json!({
+            "recording": recorder::is_recording(),
+            "playbacks": recorder::playback_ids(),
+            "eventCount": recorder::events().len(),
+        })),
+        "/events" => (200, json!(recorder::events())),
+        _ => (404, json!({ "error": "not found" })),
+    }
+}
+
+/// 是否為本機程式送出的請求 (瀏覽器的跨站請求一定帶有 Origin；DNS rebinding 的 Host 不是本機位址)
+/// # 參數
+/// - `origin` - Option<&str> Origin 標頭
+/// - `host` - Option<&str> Host 標頭 (沒有時視為本機，例如 HTTP/1.0 的用戶端)
+/// # 返回值
+/// - bool
+fn is_local_request(origin: Option<&str>, host: Option<&str>) -> bool {
+
+    if origin.is_some() { return false; }
+
+    let Some(host) = host else { return true; };
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+
+    name.eq_ignore_ascii_case("localhost") || name == "127.0.0.1" || name == "::1"
+}
+
+/// 驗證 Authorization 標頭 (只有 Bearer 不分大小寫，token 必須完全相同)
+/// # 參數
+/// - `authorization` - Option<&str> Authorization 標頭
+/// - `token` - &str 驗證用的 token
+/// # 返回值
+/// - bool
+fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+
+    let Some((scheme, value)) = authorization.and_then(|value| value.split_once(' ')) else { return false; };
+    scheme.eq_ignore_ascii_case("bearer") && constant_time_eq(value.trim().as_bytes(), token.as_bytes())
+}
+
+/// 比較兩段內容是否相同 (比較時間只與長度有關，不會因為第一個不同的位置而提早結束)
+/// # 參數
+/// - `left` - &[u8]
+/// - `right` - &[u8]
+/// # 返回值
+/// - bool
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).fold(0u8, |difference, (left, right)| difference | (left ^ right)) == 0
+}
+
+/// HTTP 狀態碼的說明文字
+/// # 參數
+/// - `status` - u16
+/// # 返回值
+/// - &'static str
+fn status_text(status: u16) -> &'static str {
+
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn token_is_compared_exactly() {
+        assert!(is_authorized(Some("Bearer abc"), "abc"));
+        assert!(is_authorized(Some("bearer abc"), "abc"));
+        assert!(!is_authorized(Some("Bearer ABC"), "abc"));
+        assert!(!is_authorized(Some("Bearer abcd"), "abc"));
+        assert!(!is_authorized(Some("Basic abc"), "abc"));
+        assert!(!is_authorized(None, "abc"));
+    }
+
+    #[test]
+    fn browser_and_rebound_requests_are_rejected() {
+        assert!(is_local_request(None, Some("127.0.0.1:8080")));
+        assert!(is_local_request(None, Some("localhost")));
+        assert!(is_local_request(None, Some("[::1]:8080")));
+        assert!(is_local_request(None, None));
+        assert!(!is_local_request(Some("https://example.com"), Some("127.0.0.1:8080")));
+        assert!(!is_local_request(Some("null"), Some("127.0.0.1:8080")));
+        assert!(!is_local_request(None, Some("evil.example.com:8080")));
+
+        let (status, _) = route("POST /start HTTP/1.1\r\nHost: 127.0.0.1:8080\r\nOrigin: https://example.com\r\n", None);
+        assert_eq!(status, 403);
+    }
+}
@@ -0,0 +1,158 @@
+//! 鍵盤配置的轉換 (QWERTY / Dvorak / Colemak) 與按鍵對應的字元
+//! - 錄製到的按鍵是實體位置，換到不同配置的系統回放時，需換成能打出相同字元的實體按鍵
+//! - 偵測系統目前的配置：macOS 讀取 HIToolbox 的設定 (與 TIS 目前的輸入來源相同)，Linux 以 `setxkbmap -query` 查詢 (只支援 X11)，Windows 與 Wayland 尚未支援
+
+use std::process::Command;
+use rdev::Key;
+
+/// 參與轉換的實體按鍵 (依 QWERTY 的位置排列)
+const PHYSICAL_KEYS: [Key; 35] = [
+    Key::Minus, Key::Equal,
+    Key::KeyQ, Key::KeyW, Key::KeyE, Key::KeyR, Key::KeyT, Key::KeyY, Key::KeyU, Key::KeyI, Key::KeyO, Key::KeyP, Key::LeftBracket, Key::RightBracket,
+    Key::KeyA, Key::KeyS, Key::KeyD, Key::KeyF, Key::KeyG, Key::KeyH, Key::KeyJ, Key::KeyK, Key::KeyL, Key::SemiColon, Key::Quote,
+    Key::KeyZ, Key::KeyX, Key::KeyC, Key::KeyV, Key::KeyB, Key::KeyN, Key::KeyM, Key::Comma, Key::Dot, Key::Slash,
+];
+
+const QWERTY: &str = "-=qwertyuiop[]asdfghjkl;'zxcvbnm,./";
+const DVORAK: &str = "[]',.pyfgcrl/=aoeuidhtns-;qjkxbmwvz";
+const COLEMAK: &str = "-=qwfpgjluy;[]arstdhneio'zxcvbkm,./";
+
+/// 取得配置名稱對應的字元表 (與 PHYSICAL_KEYS 的順序相同)
+/// # 參數
+/// - `name` - &str 配置名稱 (不分大小寫)
+/// # 返回值
+/// - Result<Vec<char>, String> - 未知的配置名稱時返回錯誤
+fn layout_table(name: &str) -> Result<Vec<char>, String> {
+
+    let table = match name.to_ascii_lowercase().as_str() {
+        "qwerty" => QWERTY,
+        "dvorak" => DVORAK,
+        "colemak" => COLEMAK,
+        _ => return Err(format!("未知的鍵盤配置: {}", name)),
+    };
+
+    Ok(table.chars().collect())
+}
+
+/// 建立兩個配置之間的按鍵轉換
+/// # 參數
+/// - `from` - &str 錄製時的配置名稱
+/// - `to` - &str 回放時的配置名稱
+/// # 返回值
+/// - Result<impl Fn(Key) -> Key, String> - 轉換函式 (不在表內的按鍵保持不變)
+pub fn key_mapper(from: &str, to: &str) -> Result<impl Fn(Key) -> Key, String> {
+
+    let from_table = layout_table(from)?;
+    let to_table = layout_table(to)?;
+
+    Ok(move |key: Key| {
+        let character = PHYSICAL_KEYS.iter().position(|physical| *physical == key).map(|index| from_table[index]);
+        let target = character.and_then(|character| to_table.iter().position(|target| *target == character));
+        target.map_or(key, |index| PHYSICAL_KEYS[index])
+    })
+}
+
+/// 偵測系統目前使用的鍵盤配置
+/// # 返回值
+/// - Option<String> - 可轉換的配置返回 "qwerty" / "dvorak" / "colemak"，其它配置返回系統的識別名稱 (例如 "com.apple.keylayout.German"、"de")，無法取得時返回 None
+pub fn detect_layout() -> Option<String> {
+
+    let output = layout_command()?.output().ok().filter(|output| output.status.success())?;
+    let identifier = parse_layout(&String::from_utf8(output.stdout).ok()?)?;
+    let lowercase = identifier.to_ascii_lowercase();
+
+    let name = if lowercase.contains("dvorak") {
+        "dvorak"
+    } else if lowercase.contains("colemak") {
+        "colemak"
+    } else if QWERTY_LAYOUTS.iter().any(|layout| lowercase == *layout) {
+        "qwerty"
+    } else {
+        return Some(identifier);
+    };
+
+    Some(name.to_string())
+}
+
+/// 視為 QWERTY 的系統配置識別名稱 (小寫)
+const QWERTY_LAYOUTS: [&str; 5] = ["com.apple.keylayout.us", "com.apple.keylayout.abc", "com.apple.keylayout.british", "us", "gb"];
+
+#[cfg(target_os = "macos")]
+fn layout_command() -> Option<Command> {
+    let mut command = Command::new("defaults");
+    command.args(["read", "com.apple.HIToolbox", "AppleCurrentKeyboardLayoutInputSourceID"]);
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn parse_layout(text: &str) -> Option<String> {
+    let identifier = text.trim();
+    if identifier.is_empty() { None } else { Some(identifier.to_string()) }
+}
+
+#[cfg(target_os = "linux")]
+fn layout_command() -> Option<Command> {
+    let mut command = Command::new("setxkbmap");
+    command.arg("-query");
+    Some(command)
+}
+
+/// 解析 `setxkbmap -query` 的輸出 (有 variant 時以 "配置(variant)" 表示，例如 "us(dvorak)")
+#[cfg(target_os = "linux")]
+fn parse_layout(text: &str) -> Option<String> {
+
+    let value = |label: &str| text.lines().find_map(|line| line.strip_prefix(label)).map(str::trim).filter(|value| !value.is_empty());
+    let layout = value("layout:")?;
+
+    Some(match value("variant:") {
+        Some(variant) => format!("{}({})", layout, variant),
+        None => layout.to_string(),
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn layout_command() -> Option<Command> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn parse_layout(_text: &str) -> Option<String> {
+    None
+}
+
+/// 取得按鍵在 US QWERTY 配置下打出的字元
+/// # 參數
+/// - `key` - Key
+/// - `is_shift` - bool 是否按住 Shift
+/// # 返回值
+/// - Option<char> - 不會打出字元的按鍵 (方向鍵、功能鍵、修飾鍵等) 返回 None
+pub fn key_char(key: Key, is_shift: bool) -> Option<char> {
+
+    if let Some(index) = PHYSICAL_KEYS.iter().position(|physical| *physical == key) {
+        let character = QWERTY.chars().nth(index)?;
+        if !is_shift { return Some(character); }
+        if character.is_ascii_lowercase() { return Some(character.to_ascii_uppercase()); }
+        return SHIFTED_SYMBOLS.iter().find(|(plain, _)| *plain == character).map(|(_, shifted)| *shifted);
+    }
+
+    let (plain, shifted) = match key {
+        Key::Num1 => ('1', '!'), Key::Num2 => ('2', '@'), Key::Num3 => ('3', '#'), Key::Num4 => ('4', '$'), Key::Num5 => ('5', '%'),
+        Key::Num6 => ('6', '^'), Key::Num7 => ('7', '&'), Key::Num8 => ('8', '*'), Key::Num9 => ('9', '('), Key::Num0 => ('0', ')'),
+        Key::BackQuote => ('`', '~'),
+        Key::BackSlash | Key::IntlBackslash => ('\\', '|'),
+        Key::Space => (' ', ' '),
+        Key::Tab => ('\t', '\t'),
+        Key::Return | Key::KpReturn => ('\n', '\n'),
+        Key::Kp0 => ('0', '0'), Key::Kp1 => ('1', '1'), Key::Kp2 => ('2', '2'), Key::Kp3 => ('3', '3'), Key::Kp4 => ('4', '4'),
+        Key::Kp5 => ('5', '5'), Key::Kp6 => ('6', '6'), Key::Kp7 => ('7', '7'), Key::Kp8 => ('8', '8'), Key::Kp9 => ('9', '9'),
+        Key::KpMinus => ('-', '-'), Key::KpPlus => ('+', '+'), Key::KpMultiply => ('*', '*'), Key::KpDivide => ('/', '/'),
+        _ => return None,
+    };
+
+    Some(if is_shift { shifted } else { plain })
+}
+
+/// 符號鍵按住 Shift 時的字元 (US QWERTY)
+const SHIFTED_SYMBOLS: [(char, char); 11] = [
+    ('-', '_'), ('=', '+'), ('[', '{'), (']', '}'), (';', ':'), ('\'', '"'), (',', '<'), ('.', '>'), ('/', '?'), ('`', '~'), ('\\', '|'),
+];
@@ -1,148 +1,238 @@
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+pub mod recorder;
+pub mod storage;
+pub mod layout;
+pub mod clock;
+pub mod focus;
+pub mod locks;
+pub mod text_input;
+pub mod media;
+pub mod priority;
+#[cfg(feature = "http-api")]
+pub mod http_api;
+#[cfg(feature = "screen-capture")]
+pub mod screen;
+#[cfg(feature = "demo-gif")]
+pub mod demo;
+#[cfg(all(target_os = "linux", feature = "linux-uinput"))]
+pub mod uinput;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::result::Result;
 use std::thread::{spawn, sleep, JoinHandle};
-use tauri::{AppHandle, Emitter};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
 use lazy_static::lazy_static;
-use rdev::{listen, simulate, Event, EventType, Key, ListenError};
-use rdev::EventType::{KeyPress, KeyRelease, MouseMove};
+use serde::Serialize;
+use rdev::ListenError;
 use global_hotkey::{GlobalHotKeyManager, GlobalHotKeyEvent, HotKeyState};
-use global_hotkey::hotkey::{HotKey, Code, Modifiers};
+use global_hotkey::hotkey::HotKey;
+
+use recorder::{Annotation, CoordTransform, DangerFinding, DebugState, DiffEntry, EventPage, PlaybackOutcome, PrivacyReport, PrivacyScanSettings, ScheduleInfo, SimulateCapabilities, StoredEvent, TrackMute, Tracks};
+use storage::RecordInfo;
+
+/// 版本與建置資訊
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppInfo {
+    version: &'static str,
+    git_sha: &'static str,
+    rdev_version: &'static str,
+    platform: &'static str,
+    build_profile: &'static str,
+}
+
+/// 目前已註冊的快速鍵 (沒有註冊時為 None)
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeyInfo {
+    record: Option<String>,
+    playback: Option<String>,
+    repeat: Option<String>,
+    panic_stop: Option<String>,
+}
+
+/// 註冊失敗的快速鍵 (發送給前端)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeyFailure {
+    action: String,
+    hotkey: String,
+    error: String,
+}
+
+/// 鍵盤監聽執行緒的狀態 (定期發送給前端)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListenerHealth {
+    is_alive: bool,
+    last_error: Option<String>,
+}
 
-struct Recorder {
-    events: Vec<Event>,
+/// 回放結束後比對螢幕截圖的結果 (發送給前端)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifyResult {
+    reference_path: String,
+    passed: bool,
+    difference: Option<f64>,
+    tolerance: f64,
+    error: Option<String>,
 }
 
-static IS_LISTENING: AtomicBool = AtomicBool::new(false);
-static STOP_PLAYBACK: AtomicBool = AtomicBool::new(true);
+static NOTIFY_ON_COMPLETE: AtomicBool = AtomicBool::new(false);
+static HEALTH_INTERVAL_MS: AtomicU64 = AtomicU64::new(2000);
+
+// 用於存儲 AppHandle 的全局變數 (發送事件與通知用)
+lazy_static! {
+    static ref APP_HANDLE: Arc<Mutex<Option<AppHandle>>> = Arc::new(Mutex::new(None));
+}
 
-// 用於存儲錄製事件的全局變數
+// 用於存儲已註冊快速鍵的全局變數 (GlobalHotKeyManager 無法列出已註冊的快速鍵)
 lazy_static! {
-    static ref RECORDER: Arc<Mutex<Recorder>> = Arc::new(Mutex::new(Recorder { events: Vec::new() }));
+    static ref REGISTERED_HOTKEYS: Arc<Mutex<HotkeyInfo>> = Arc::new(Mutex::new(HotkeyInfo::default()));
+}
+
+// 回放結束後要比對的參考圖片與容許的差異程度 (沒有設定時為 None)
+lazy_static! {
+    static ref VERIFY_SCREENSHOT: Arc<Mutex<Option<(String, f64)>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲滑鼠座標預設組合的全局變數 (名稱對應縮放與位移，儲存在設定資料夾的 coord_presets.json)
+lazy_static! {
+    static ref COORD_PRESETS: Arc<Mutex<BTreeMap<String, CoordTransform>>> = Arc::new(Mutex::new(BTreeMap::new()));
 }
 
 // 用於存儲執行緒 handles 的全局變數
 lazy_static! {
-    static ref THREAD_HANDLES: Arc<Mutex<Option<(std::thread::JoinHandle<Result<(), ListenError>>, std::thread::JoinHandle<()>)>>> = 
+    static ref THREAD_HANDLES: Arc<Mutex<Option<(std::thread::JoinHandle<Result<(), ListenError>>, std::thread::JoinHandle<()>)>>> =
         Arc::new(Mutex::new(None));
 }
 
-// MARK: 處理各種事件的具體行為 
-/// 處理鍵盤按下事件 (錄製)
+// MARK: 共用工具
+/// 取得互斥鎖，若先前有執行緒在持有鎖時 panic 導致鎖中毒 (poisoned)，則取回內部資料繼續使用
 /// # 參數
-/// - `event` - Event
-/// - `key` - Key
-fn _key_press_action(event: &Event, key: Key) {
-    record_event(event, key);
+/// - `mutex` - &Mutex<T>
+/// # 返回值
+/// - MutexGuard<T>
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| {
+        println!("[Warning] 互斥鎖已中毒，取回資料後繼續執行");
+        error.into_inner()
+    })
 }
 
-/// 處理鍵盤放開事件
-/// # 參數
-/// - `event` - Event
-/// - `key` - Key
-fn _key_release_action(_event: &Event, _key: Key) {}
+#[cfg(test)]
+static TEST_SERIAL: Mutex<()> = Mutex::new(());
 
-/// 處理滑鼠移動事件
-/// # 參數
-/// - `event` - Event
-/// - `x` - f64
-/// - `y` - f64
-fn _mouse_move_action(_event: &Event, _x: f64, _y: f64) {}
+/// 讓會修改全局設定的測試依序執行 (持有返回的 guard 直到測試結束)
+/// # 返回值
+/// - MutexGuard<()>
+#[cfg(test)]
+pub(crate) fn serial_test() -> MutexGuard<'static, ()> { lock_or_recover(&TEST_SERIAL) }
 
-/// 模擬鍵盤按下事件
+/// 發送事件給前端 (尚未初始化 AppHandle 時忽略)
 /// # 參數
-/// - `key` - Key
-fn _play_key_press(key: Key) {
-    
-    match simulate(&EventType::KeyPress(key)) {
-        Ok(_) => println!("成功模擬按鍵: {:?}", key),
-        Err(error) => println!("模擬鍵盤事件失敗: {:?}", error),
-    }
+/// - `event` - &str 事件名稱
+/// - `payload` - S 事件內容
+fn emit_event<S: Serialize + Clone>(event: &str, payload: S) {
+    if let Some(app_handle) = &*lock_or_recover(&APP_HANDLE) { let _ = app_handle.emit(event, payload); }
 }
 
-/// 模擬鍵盤放開事件
+/// 完成錄製或回放時發送系統通知 (需開啟 NOTIFY_ON_COMPLETE)
 /// # 參數
-/// - `key` - Key
-fn _play_key_release(key: Key) {
-    
-    match simulate(&EventType::KeyRelease(key)) {
-        Ok(_) => println!("成功模擬放開按鍵: {:?}", key),
-        Err(error) => println!("模擬鍵盤事件失敗: {:?}", error),
-    }
-}
+/// - `body` - &str 通知內容
+fn notify_complete(body: &str) {
 
-/// 模擬滑鼠移動事件
-/// # 參數
-/// - `x` - f64
-/// - `y` - f64
-/// - `delay_time` - u64 延遲時間（毫秒）
-fn _play_mouse_move(_x: f64, _y: f64) {}
+    if !NOTIFY_ON_COMPLETE.load(Ordering::SeqCst) { return; }
 
-/// 回調事件處理 (鍵盤 / 滑鼠)
-/// # 參數
-/// - `event` - Event
-fn callback(event: Event) {
-
-    match event.event_type {
-        KeyPress(key) => { _key_press_action(&event, key); }
-        KeyRelease(key) => { _key_release_action(&event, key); }
-        MouseMove { x, y } => { _mouse_move_action(&event, x, y); }
-        _ => {}
+    if let Some(app_handle) = &*lock_or_recover(&APP_HANDLE) {
+        if let Err(error) = app_handle.notification().builder().title("鍵盤錄製器").body(body).show() {
+            println!("[Error] 發送通知失敗: {:?}", error);
+        }
     }
 }
 
-// MARK: 相關功能實現
-/// 開始監聽鍵盤和滑鼠事件 (新執行緒)
-/// # 返回值
-/// - JoinHandle<Result<(), ListenError>> - 執行緒的 handle，可用於檢查執行狀態
-fn listen_keyboard() -> JoinHandle<Result<(), ListenError>> {
-    
-    spawn(move || {
-        listen_keyboard_action().map_err(|error| { 
-            println!("[Error] 鍵盤監聽執行緒發生錯誤: {:?}", error);
-            error
-        })
-    })
+/// 依回放結果發送通知
+/// # 參數
+/// - `is_stop` - bool 回放是否被中途停止
+fn notify_playback_complete(is_stop: bool) {
+    notify_complete(if is_stop { "回放已中途停止" } else { "回放已完成" });
+    if !is_stop { verify_screenshot(); }
 }
 
-/// 開始監聽鍵盤和滑鼠事件
-/// # 返回值
-/// - Result<(), ListenError>
-fn listen_keyboard_action() -> Result<(), ListenError> {
-
-    match listen(callback) {
-        Ok(()) => Ok(()),
-        Err(error) => { Err(error) },
-    }
+/// 回放完成後擷取主螢幕並與參考圖片比較，發送 verify-result 事件 (沒有設定參考圖片時略過)
+fn verify_screenshot() {
 
-    // let result = listen(callback).map_err(|error| { error })
-}
+    let Some((reference_path, tolerance)) = lock_or_recover(&VERIFY_SCREENSHOT).clone() else { return; };
 
-/// 記錄事件值
-/// # 參數
-/// - `event` - Event
-/// - `key` - Key
-fn record_event(event: &Event, _key: Key) {
+    let result = if std::path::Path::new(&reference_path).is_file() {
+        compare_screen_with(&reference_path)
+    } else {
+        Err(format!("找不到參考圖片: {}", reference_path))
+    };
 
-    if !IS_LISTENING.load(Ordering::SeqCst) { return; }
+    let result = match result {
+        Ok(difference) => VerifyResult { reference_path, passed: difference <= tolerance, difference: Some(difference), tolerance, error: None },
+        Err(error) => VerifyResult { reference_path, passed: false, difference: None, tolerance, error: Some(error) },
+    };
 
-    let mut recorder = RECORDER.lock().unwrap();
-    recorder.events.push(event.clone());
+    emit_event("verify-result", result);
 }
 
+// MARK: 相關功能實現
 /// 註冊全局快速鍵行為 (新執行緒)
 /// # 返回值
 /// - JoinHandle<()> - 執行緒的 handle，可用於檢查執行狀態
 fn register_hotkey_action(app_handle: AppHandle) -> JoinHandle<()> {
 
     spawn(move || {
-        
-        let hotkey_manager = GlobalHotKeyManager::new().expect("Failed to create GlobalHotKey manager");
-        let record_hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyS);
-        let playback_hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyP);
-        
-        hotkey_manager.register(record_hotkey.clone()).expect("Failed to register Command+Shift+G hotkey");
-        hotkey_manager.register(playback_hotkey.clone()).expect("Failed to register Command+Option+G hotkey");
+
+        let hotkeys = recorder::default_handle().hotkeys();
+        let record_hotkey = hotkeys.record;
+        let playback_hotkey = hotkeys.playback;
+        let repeat_hotkey = hotkeys.repeat;
+        let panic_hotkey = recorder::panic_hotkey();
+        let actions = [("record", &record_hotkey), ("playback", &playback_hotkey), ("repeat", &repeat_hotkey), ("panicStop", &panic_hotkey)];
+
+        let hotkey_manager = match GlobalHotKeyManager::new() {
+            Ok(hotkey_manager) => hotkey_manager,
+            Err(error) => {
+                let failures: Vec<HotkeyFailure> = actions.iter().map(|(action, hotkey)| {
+                    HotkeyFailure { action: action.to_string(), hotkey: hotkey_name(hotkey), error: error.to_string() }
+                }).collect();
+                let _ = app_handle.emit("HotkeyRegistrationFailed", failures);
+                return;
+            }
+        };
+
+        // 每個快速鍵分開註冊，保留成功的並回報失敗的 (例如已被其它程式使用)
+        let mut failures = Vec::new();
+        let mut registered = Vec::new();
+
+        for (action, hotkey) in actions.iter() {
+            match hotkey_manager.register((*hotkey).clone()) {
+                Ok(()) => registered.push(*action),
+                Err(error) => failures.push(HotkeyFailure { action: action.to_string(), hotkey: hotkey_name(hotkey), error: error.to_string() }),
+            }
+        }
+
+        let registered_name = |action: &str, hotkey: &HotKey| registered.contains(&action).then(|| hotkey_name(hotkey));
+
+        *lock_or_recover(&REGISTERED_HOTKEYS) = HotkeyInfo {
+            record: registered_name("record", &record_hotkey),
+            playback: registered_name("playback", &playback_hotkey),
+            repeat: registered_name("repeat", &repeat_hotkey),
+            panic_stop: registered_name("panicStop", &panic_hotkey),
+        };
+
+        if !failures.is_empty() {
+            println!("[Warning] 部分快速鍵註冊失敗: {}", failures.iter().map(|failure| failure.hotkey.as_str()).collect::<Vec<_>>().join(", "));
+            let _ = app_handle.emit("HotkeyRegistrationFailed", failures);
+        }
 
         loop {
             if let Ok(event) = GlobalHotKeyEvent::receiver().recv() {
@@ -150,6 +240,8 @@ fn register_hotkey_action(app_handle: AppHandle) -> JoinHandle<()> {
                 if event.state == HotKeyState::Pressed {
                     if event.id() == record_hotkey.id() { let _ = app_handle.emit("StartRecord", 0); }
                     else if event.id() == playback_hotkey.id() { let _ = app_handle.emit("PlayRecord", 0); }
+                    else if event.id() == repeat_hotkey.id() { spawn_repeat_playback(); }
+                    else if event.id() == panic_hotkey.id() { recorder::panic_stop(); }
                 }
             } else {
                 break;
@@ -158,108 +250,604 @@ fn register_hotkey_action(app_handle: AppHandle) -> JoinHandle<()> {
     })
 }
 
+/// 在新執行緒重複上一次的回放 (不阻塞快速鍵的監聽，回放中仍可使用緊急停止)
+fn spawn_repeat_playback() {
+
+    spawn(|| {
+        if let Err(error) = recorder::repeat_last_playback() {
+            println!("[Warning] 無法重複上一次的回放: {}", error);
+            emit_event("RepeatPlaybackFailed", error);
+        }
+    });
+}
+
+/// 快速鍵的文字表示 (例如 `shift+control+KeyS`)
+/// # 參數
+/// - `hotkey` - &HotKey
+/// # 返回值
+/// - String
+fn hotkey_name(hotkey: &HotKey) -> String {
+    hotkey.clone().into_string()
+}
+
 // MARK: 使用到的功能
 /// 初始化設置
 /// # 返回值
 /// - Result<(JoinHandle<Result<(), ListenError>>, JoinHandle<()>), String>
 fn init_setting(app_handle: AppHandle) -> Result<(JoinHandle<Result<(), ListenError>>, JoinHandle<()>), String> {
-    let keyboard_handle = listen_keyboard();
+    let keyboard_handle = recorder::listen_keyboard();
     let hotkey_handle = register_hotkey_action(app_handle);
     Ok((keyboard_handle, hotkey_handle))
 }
 
-/// 開始監聽錄製鍵盤事件
+/// 檢查背景的鍵盤監聽執行緒（thread）是否還在執行中
 /// # 返回值
-/// - bool - 返回當前是否在監聽狀態
-fn start_listen() -> bool {
-    STOP_PLAYBACK.store(false, Ordering::SeqCst);
-    IS_LISTENING.store(true, Ordering::SeqCst);
-    RECORDER.lock().unwrap().events.clear();
+/// - bool - 如果執行緒還在運行，返回 true；否則返回 false
+fn check_keyboard_status() -> bool {
+    lock_or_recover(&THREAD_HANDLES)
+        .as_ref()
+        .is_some_and(|(keyboard_handle, _)| !keyboard_handle.is_finished())
+}
+
+/// 定期發送鍵盤監聽執行緒的狀態 (新執行緒；"listener-health" 事件，間隔為 0 時暫停發送)
+fn spawn_health_heartbeat() {
+
+    spawn(|| loop {
 
-    IS_LISTENING.load(Ordering::SeqCst)
+        let interval_ms = HEALTH_INTERVAL_MS.load(Ordering::SeqCst);
+        if interval_ms == 0 { sleep(Duration::from_millis(250)); continue; }
+
+        emit_event("listener-health", ListenerHealth { is_alive: check_keyboard_status(), last_error: recorder::last_listen_error() });
+        sleep(Duration::from_millis(interval_ms));
+    });
 }
 
-/// 停止監聽錄製鍵盤事件
+/// 設定發送鍵盤監聽執行緒狀態的間隔 (下一次發送後生效)
 /// # 參數
-/// - `is_hotkey` - bool 是否通過熱鍵停止錄製
+/// - `ms` - u64 毫秒 (預設為 2000，0 表示不發送)
 /// # 返回值
-/// - bool - 返回當前是否仍在監聽狀態
-fn stop_listen(is_hotkey: bool) -> bool {
-    STOP_PLAYBACK.store(true, Ordering::SeqCst);
-    IS_LISTENING.store(false, Ordering::SeqCst);
-
-    if is_hotkey {
-        let mut recorder = RECORDER.lock().unwrap();
-        // Pop last 3 events if possible
-        for _ in 0..3 {
-            if recorder.events.is_empty() { break; }
-            recorder.events.pop();
-        }
+/// - u64 - 返回當前設定
+fn update_health_interval_ms(ms: u64) -> u64 {
+    HEALTH_INTERVAL_MS.store(ms, Ordering::SeqCst);
+    HEALTH_INTERVAL_MS.load(Ordering::SeqCst)
+}
+
+/// 取得版本與建置資訊
+/// # 返回值
+/// - AppInfo
+fn current_app_info() -> AppInfo {
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: env!("GIT_SHA"),
+        rdev_version: env!("RDEV_VERSION"),
+        platform: std::env::consts::OS,
+        build_profile: env!("BUILD_PROFILE"),
     }
+}
 
-    IS_LISTENING.load(Ordering::SeqCst)
+/// 取得滑鼠座標預設組合的檔案路徑
+/// # 返回值
+/// - Option<PathBuf> - 尚未初始化 AppHandle 或無法取得設定資料夾時返回 None
+fn coord_presets_path() -> Option<PathBuf> {
+    let app_handle = lock_or_recover(&APP_HANDLE).clone()?;
+    app_handle.path().app_config_dir().ok().map(|dir| dir.join("coord_presets.json"))
 }
 
-/// 檢查背景的鍵盤監聽執行緒（thread）是否還在執行中
+/// 從設定資料夾載入滑鼠座標預設組合 (檔案不存在時略過)
+fn load_coord_presets() {
+
+    let Some(path) = coord_presets_path() else { return; };
+    let Ok(text) = std::fs::read_to_string(&path) else { return; };
+
+    match serde_json::from_str::<BTreeMap<String, CoordTransform>>(&text) {
+        Ok(presets) => *lock_or_recover(&COORD_PRESETS) = presets,
+        Err(error) => println!("[Error] 無法讀取座標預設組合 {}: {}", path.display(), error),
+    }
+}
+
+/// 新增或覆蓋滑鼠座標預設組合，並寫入設定資料夾
+/// # 參數
+/// - `name` - &str 名稱
+/// - `preset` - CoordTransform
 /// # 返回值
-/// - bool - 如果執行緒還在運行，返回 true；否則返回 false
-fn check_keyboard_status() -> bool {
-    if let Some((keyboard_handle, _)) = &*THREAD_HANDLES.lock().unwrap() { return !keyboard_handle.is_finished(); }
-    return false;
-    // THREAD_HANDLES.lock().unwrap()
-    //     .as_ref()
-    //     .map_or(false, |(keyboard_handle, _)| !keyboard_handle.is_finished())
+/// - Result<(), String> - 名稱為空、數值不正確或寫入失敗時返回錯誤
+fn save_coord_preset_named(name: &str, preset: CoordTransform) -> Result<(), String> {
+
+    let name = name.trim();
+    if name.is_empty() { return Err("名稱不能為空".to_string()); }
+    preset.validate()?;
+
+    let path = coord_presets_path().ok_or("無法取得設定資料夾")?;
+    let mut presets = lock_or_recover(&COORD_PRESETS);
+    presets.insert(name.to_string(), preset);
+
+    let text = serde_json::to_string_pretty(&*presets).map_err(|error| error.to_string())?;
+    if let Some(dir) = path.parent() { std::fs::create_dir_all(dir).map_err(|error| error.to_string())?; }
+    std::fs::write(&path, text).map_err(|error| error.to_string())
 }
 
-/// 回放錄製的鍵盤事件
+/// 套用滑鼠座標預設組合 (設定回放時的縮放與位移)
+/// # 參數
+/// - `name` - &str 名稱
 /// # 返回值
-/// - bool - 如果回放過程中被停止，返回 true；否則返回 false
-fn playback() -> bool {
-    
-    let events = RECORDER.lock().unwrap().events.clone();
-    if events.is_empty() { return false; }
+/// - Result<CoordTransform, String> - 找不到該名稱或數值不正確時返回錯誤
+fn apply_coord_preset_named(name: &str) -> Result<CoordTransform, String> {
+    let preset = *lock_or_recover(&COORD_PRESETS).get(name).ok_or_else(|| format!("找不到座標預設組合: {}", name))?;
+    recorder::update_mouse_transform(Some(preset))?;
+    Ok(preset)
+}
 
-    let mut is_stop = false;
+/// 設定回放完成後要比對的參考圖片 (多螢幕時只比對主螢幕，需啟用 screen-capture 功能)
+/// # 參數
+/// - `reference_path` - Option<String> 參考圖片的檔案路徑 (None 表示不比對)
+/// - `tolerance` - f64 容許的差異程度 (0.0 - 1.0，0.0 表示必須完全相同)
+/// # 返回值
+/// - Option<(String, f64)> - 返回當前設定
+fn update_verify_screenshot(reference_path: Option<String>, tolerance: f64) -> Option<(String, f64)> {
 
-    STOP_PLAYBACK.store(false, Ordering::SeqCst);
+    let tolerance = if tolerance.is_finite() { tolerance.clamp(0.0, 1.0) } else { 0.0 };
+    let mut verify = lock_or_recover(&VERIFY_SCREENSHOT);
 
-    let mut last_event_time = events[0].time;
+    *verify = reference_path.map(|path| (path, tolerance));
+    verify.clone()
+}
 
-    for event in events.iter() {
-        
-        if STOP_PLAYBACK.load(Ordering::SeqCst) { is_stop = true; break; }
+/// 設定錄製停止或回放結束時是否發送系統通知
+/// # 參數
+/// - `enabled` - bool 是否發送通知
+/// # 返回值
+/// - bool - 返回當前設定
+fn update_notify_on_complete(enabled: bool) -> bool {
+    NOTIFY_ON_COMPLETE.store(enabled, Ordering::SeqCst);
+    NOTIFY_ON_COMPLETE.load(Ordering::SeqCst)
+}
 
-        // 計算與上一個事件之間的時間差並等待
-        let delay = event.time.duration_since(last_event_time).unwrap_or_default();
-        sleep(delay);
+/// 啟動本機 HTTP 控制介面
+/// # 參數
+/// - `port` - u16 連接埠
+/// - `token` - Option<String> 驗證用的 token
+/// # 返回值
+/// - Result<u16, String> - 實際使用的連接埠
+#[cfg(feature = "http-api")]
+fn start_http_server(port: u16, token: Option<String>) -> Result<u16, String> { http_api::start(port, token) }
 
-        // 根據事件類型直接模擬，不再傳入延遲時間
-        match event.event_type {
-            KeyPress(key) => { _play_key_press(key); }
-            KeyRelease(key) => {  _play_key_release(key); }
-            MouseMove { x, y } => { _play_mouse_move(x, y); }
-            _ => {}
-        }
+#[cfg(not(feature = "http-api"))]
+fn start_http_server(_port: u16, _token: Option<String>) -> Result<u16, String> { Err("未啟用 http-api 功能".to_string()) }
 
-        last_event_time = event.time;
-    }
+/// 停止本機 HTTP 控制介面
+/// # 返回值
+/// - bool - 原本有在執行時返回 true
+#[cfg(feature = "http-api")]
+fn stop_http_server() -> bool { http_api::stop() }
+
+#[cfg(not(feature = "http-api"))]
+fn stop_http_server() -> bool { false }
 
-    is_stop
+/// 擷取主螢幕並存成 PNG (多螢幕時只擷取主螢幕)
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<(), String>
+#[cfg(feature = "screen-capture")]
+fn capture_screen_to(path: &str) -> Result<(), String> { screen::capture_primary_screen(path) }
+
+#[cfg(not(feature = "screen-capture"))]
+fn capture_screen_to(_path: &str) -> Result<(), String> { Err("未啟用 screen-capture 功能".to_string()) }
+
+/// 把錄製的滑鼠路徑畫在主螢幕截圖上並輸出成 GIF 動畫
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// - `width` - Option<u32> 輸出的寬度
+/// # 返回值
+/// - Result<usize, String> - 輸出的畫格數量
+#[cfg(feature = "demo-gif")]
+fn export_demo_gif_to(path: &str, width: Option<u32>) -> Result<usize, String> { recorder::export_demo_gif(path, width) }
+
+#[cfg(not(feature = "demo-gif"))]
+fn export_demo_gif_to(_path: &str, _width: Option<u32>) -> Result<usize, String> { Err("未啟用 demo-gif 功能".to_string()) }
+
+/// 擷取主螢幕並與參考圖片比較
+/// # 參數
+/// - `reference_path` - &str 參考圖片的檔案路徑
+/// # 返回值
+/// - Result<f64, String> - 差異程度 (0.0 - 1.0)
+#[cfg(feature = "screen-capture")]
+fn compare_screen_with(reference_path: &str) -> Result<f64, String> { screen::compare_primary_screen(reference_path) }
+
+#[cfg(not(feature = "screen-capture"))]
+fn compare_screen_with(_reference_path: &str) -> Result<f64, String> { Err("未啟用 screen-capture 功能".to_string()) }
+
+/// 把錄製內容中位於某個螢幕上的滑鼠座標換算到另一個螢幕
+/// - 各螢幕的位置與大小由 xcap 取得，需要啟用 `screen-capture` 功能 (rdev 只提供主螢幕的大小)
+/// # 參數
+/// - `from_index` - usize 來源螢幕的編號
+/// - `to_index` - usize 目標螢幕的編號
+/// # 返回值
+/// - Result<usize, String> - 換算的事件數量；編號超出範圍、只有一個螢幕或未啟用 `screen-capture` 功能時返回錯誤
+#[cfg(feature = "screen-capture")]
+fn remap_displays_with(from_index: usize, to_index: usize) -> Result<usize, String> {
+
+    let displays = screen::display_bounds()?;
+    if displays.len() < 2 { return Err("只有一個螢幕，無法換算".to_string()); }
+
+    let bounds = |index: usize| displays.get(index).copied().ok_or_else(|| format!("螢幕編號超出範圍: {} (共 {} 個螢幕)", index, displays.len()));
+    recorder::remap_display(bounds(from_index)?, bounds(to_index)?)
 }
 
+#[cfg(not(feature = "screen-capture"))]
+fn remap_displays_with(_from_index: usize, _to_index: usize) -> Result<usize, String> { Err("remap_displays 需要啟用 screen-capture 功能 (以 xcap 取得各螢幕的位置與大小)".to_string()) }
+
+/// 設定 Linux 回放時是否以 uinput 虛擬裝置送出事件
+/// # 參數
+/// - `enabled` - bool
+/// # 返回值
+/// - Result<bool, String> - 返回當前是否使用 uinput
+#[cfg(all(target_os = "linux", feature = "linux-uinput"))]
+fn update_linux_uinput(enabled: bool) -> Result<bool, String> { uinput::set_enabled(enabled) }
+
+#[cfg(not(all(target_os = "linux", feature = "linux-uinput")))]
+fn update_linux_uinput(_enabled: bool) -> Result<bool, String> { Err("未啟用 linux-uinput 功能 (只支援 Linux)".to_string()) }
+
 // MARK: Tauri commands
 #[tauri::command]
-fn start_record() -> bool { start_listen() }
+fn start_record() -> bool { recorder::start_listen() }
+
+#[tauri::command]
+fn stop_record(is_hotkey: bool) -> bool { recorder::stop_listen(is_hotkey) }
+
+#[tauri::command]
+fn play_record() -> PlaybackOutcome { recorder::playback() }
+
+#[tauri::command]
+fn play_record_keyboard_only() -> PlaybackOutcome { recorder::playback_keyboard_only() }
+
+#[tauri::command]
+fn play_record_mouse_only() -> PlaybackOutcome { recorder::playback_mouse_only() }
+
+#[tauri::command]
+fn play_record_fixed_key_gap(ms: u64) -> PlaybackOutcome { recorder::playback_fixed_key_gap(ms) }
+
+#[tauri::command]
+fn play_record_window(start_ms: u64, end_ms: u64) -> bool { recorder::playback_window(start_ms, end_ms) }
+
+#[tauri::command]
+fn play_event(index: usize) -> Result<(), String> { recorder::play_event(index) }
+
+#[tauri::command]
+fn simulate_combo(combo: String) -> Result<(), String> { recorder::simulate_combo(&combo) }
+
+#[tauri::command]
+fn step_mode_begin() -> usize { recorder::step_mode_begin() }
+
+#[tauri::command]
+fn step_next() -> bool { recorder::step_next() }
+
+#[tauri::command]
+fn step_reset() -> bool { recorder::step_reset() }
+
+#[tauri::command]
+fn start_playback() -> Option<u64> { recorder::spawn_playback() }
+
+#[tauri::command]
+fn stop_playback_by_id(id: u64) -> bool { recorder::stop_playback(id) }
+
+#[tauri::command]
+fn pause_playback() -> bool { recorder::pause_playback() }
+
+#[tauri::command]
+fn resume_playback() -> bool { recorder::resume_playback() }
+
+#[tauri::command]
+fn undo_last_playback() -> Result<usize, String> { recorder::undo_last_playback() }
+
+#[tauri::command]
+fn panic_stop() -> usize { recorder::panic_stop() }
+
+#[tauri::command]
+fn active_playbacks() -> Vec<u64> { recorder::playback_ids() }
+
+#[tauri::command]
+fn playback_eta_ms() -> Option<u64> { recorder::playback_remaining_ms() }
+
+#[tauri::command]
+fn playback_position() -> Option<usize> { recorder::playback_position() }
+
+#[tauri::command]
+fn event_count() -> usize { recorder::event_count() }
+
+#[tauri::command]
+fn gap_histogram(bucket_ms: u64) -> Vec<(u64, usize)> { recorder::gap_histogram(bucket_ms) }
+
+#[tauri::command]
+fn play_playlist(paths: Vec<String>, gap_ms: u64) -> Result<(), String> { recorder::playback_playlist(&paths, gap_ms) }
+
+#[tauri::command]
+fn schedule_playback(epoch_ms: u64, slot: Option<String>) -> Result<ScheduleInfo, String> { recorder::schedule_playback_at(epoch_ms, slot) }
+
+#[tauri::command]
+fn list_schedules() -> Vec<ScheduleInfo> { recorder::list_schedules() }
+
+#[tauri::command]
+fn cancel_schedule(id: u64) -> bool { recorder::cancel_schedule(id) }
+
+#[tauri::command]
+fn cancel_scheduled() -> bool { recorder::cancel_scheduled_playback() }
+
+#[tauri::command]
+fn save_record(path: String) -> Result<(), String> { recorder::save_to_file(&path) }
+
+#[tauri::command]
+fn save_slot(name: String) -> usize { recorder::save_slot(&name) }
+
+#[tauri::command]
+fn snapshot_to_slot(name: String, overwrite: bool) -> Result<usize, String> { recorder::snapshot_to_slot(&name, overwrite) }
+
+#[tauri::command]
+fn load_slot(name: String) -> Result<usize, String> { recorder::load_slot(&name) }
 
 #[tauri::command]
-fn stop_record(is_hotkey: bool) -> bool { stop_listen(is_hotkey) }
+fn set_slot_tags(name: String, tags: Vec<String>) -> Result<(), String> { recorder::set_slot_tags(&name, tags) }
 
 #[tauri::command]
-fn play_record() -> bool { playback() }
+fn add_slot_call(index: usize, name: String) -> Result<(), String> { recorder::add_slot_call(index, &name) }
+
+#[tauri::command]
+fn remove_slot_calls(index: usize) -> usize { recorder::remove_slot_calls(index) }
+
+#[tauri::command]
+fn add_branch(index: usize, key: String, then_slot: Option<String>, else_slot: Option<String>) -> Result<(), String> { recorder::add_branch(index, &key, then_slot, else_slot) }
+
+#[tauri::command]
+fn remove_branches(index: usize) -> usize { recorder::remove_branches(index) }
+
+#[tauri::command]
+fn search_slots(query: String) -> Vec<String> { recorder::search_slots(&query) }
+
+#[tauri::command]
+fn export_csv(path: String) -> Result<usize, String> { recorder::export_csv(&path) }
+
+#[tauri::command]
+fn export_time_slice(start_ms: u64, end_ms: u64, path: String) -> Result<usize, String> { recorder::export_time_slice(start_ms, end_ms, &path) }
+
+#[tauri::command]
+fn load_record(path: String) -> Result<usize, String> { recorder::load_from_file(&path) }
+
+#[tauri::command]
+fn set_stream_to_file(path: Option<String>) -> Result<(), String> { recorder::set_stream_to_file(path.as_deref()) }
+
+#[tauri::command]
+fn recover_from_log(path: String) -> Result<usize, String> { recorder::recover_from_log(&path) }
+
+#[tauri::command]
+fn remap_layout(from: String, to: String) -> Result<(), String> { recorder::remap_layout(&from, &to) }
+
+#[tauri::command]
+fn detect_layout() -> Option<String> { layout::detect_layout() }
+
+#[tauri::command]
+fn build_from_keys(keys: Vec<String>, gap_ms: u64) -> Result<usize, String> { recorder::build_from_keys(&keys, gap_ms) }
+
+#[tauri::command]
+fn split_record(index: usize) -> Result<Vec<StoredEvent>, String> { recorder::split_record(index) }
+
+#[tauri::command]
+fn trim_after_last_key() -> usize { recorder::trim_after_last_key() }
+
+#[tauri::command]
+fn redact_range(start_ms: u64, end_ms: u64) -> Result<usize, String> { recorder::redact_range(start_ms, end_ms) }
+
+#[tauri::command]
+fn replace_key(from: String, to: String) -> Result<usize, String> { recorder::replace_key(&from, &to) }
+
+#[tauri::command]
+fn remap_displays(from_index: usize, to_index: usize) -> Result<usize, String> { remap_displays_with(from_index, to_index) }
+
+#[tauri::command]
+fn get_tracks() -> Tracks { recorder::tracks() }
+
+#[tauri::command]
+fn set_track_muted(track: String, muted: bool) -> Result<TrackMute, String> { recorder::set_track_muted(&track, muted) }
+
+#[tauri::command]
+fn repair_timing() -> usize { recorder::repair_timing() }
+
+#[tauri::command]
+fn fit_duration(target_ms: u64) -> Result<u64, String> { recorder::fit_duration(target_ms) }
+
+#[tauri::command]
+fn quantize_timing(grid_ms: u64) -> Result<usize, String> { recorder::quantize_timing(grid_ms) }
+
+#[tauri::command]
+fn set_event_enabled(index: usize, enabled: bool) -> Result<(), String> { recorder::set_event_enabled(index, enabled) }
+
+#[tauri::command]
+fn undo() -> bool { recorder::undo() }
+
+#[tauri::command]
+fn redo() -> bool { recorder::redo() }
+
+#[tauri::command]
+fn get_events() -> Vec<StoredEvent> { recorder::events() }
+
+#[tauri::command]
+fn get_events_page(offset: usize, limit: usize) -> EventPage { recorder::events_page(offset, limit) }
+
+#[tauri::command]
+fn set_event_label(index: usize, label: String) -> Result<(), String> { recorder::set_event_label(index, label) }
+
+#[tauri::command]
+fn record_annotations() -> Vec<Annotation> { recorder::annotations() }
+
+#[tauri::command]
+fn extract_text() -> String { recorder::extract_text() }
+
+#[tauri::command]
+fn privacy_scan() -> PrivacyReport { recorder::privacy_scan() }
+
+#[tauri::command]
+fn set_privacy_scan_settings(settings: PrivacyScanSettings) -> PrivacyScanSettings { recorder::update_privacy_scan_settings(settings) }
+
+#[tauri::command]
+fn set_record_info(name: String, description: String) { recorder::set_record_info(name, description) }
+
+#[tauri::command]
+fn current_session_id() -> Option<String> { recorder::current_session_id() }
+
+#[tauri::command]
+fn get_record_info() -> RecordInfo { recorder::record_info() }
+
+#[tauri::command]
+fn diff_record(path: String, timing_tolerance_ms: Option<u64>, position_tolerance: Option<f64>) -> Result<Vec<DiffEntry>, String> { recorder::diff_record(&path, timing_tolerance_ms, position_tolerance) }
+
+#[tauri::command]
+fn debug_state() -> DebugState { recorder::debug_state(check_keyboard_status()) }
+
+#[tauri::command]
+fn simulate_capabilities() -> SimulateCapabilities { recorder::simulate_capabilities() }
+
+#[tauri::command]
+fn scan_dangerous() -> Vec<DangerFinding> { recorder::scan_dangerous() }
 
 #[tauri::command]
 fn keyboard_status() -> bool { check_keyboard_status() }
 
+#[tauri::command]
+fn set_health_interval_ms(ms: u64) -> u64 { update_health_interval_ms(ms) }
+
+#[tauri::command]
+fn current_hotkeys() -> HotkeyInfo { lock_or_recover(&REGISTERED_HOTKEYS).clone() }
+
+#[tauri::command]
+fn export_schema() -> String { storage::record_schema() }
+
+#[tauri::command]
+fn app_info() -> AppInfo { current_app_info() }
+
+#[tauri::command]
+fn input_devices() -> Vec<String> { recorder::input_devices() }
+
+#[tauri::command]
+fn set_input_device(id: Option<String>) -> bool { recorder::set_input_device(id.as_deref()) }
+
+#[tauri::command]
+fn stuck_keys() -> Vec<String> { recorder::held_key_names() }
+
+#[tauri::command]
+fn release_all_keys() -> usize { recorder::release_all_held_keys() }
+
+#[tauri::command]
+fn capture_screen(path: String) -> Result<(), String> { capture_screen_to(&path) }
+
+#[tauri::command]
+fn export_demo_gif(path: String, width: Option<u32>) -> Result<usize, String> { export_demo_gif_to(&path, width) }
+
+#[tauri::command]
+fn set_verify_screenshot(reference_path: Option<String>, tolerance: f64) -> Option<(String, f64)> { update_verify_screenshot(reference_path, tolerance) }
+
+#[tauri::command]
+fn start_http(port: u16, token: Option<String>) -> Result<u16, String> { start_http_server(port, token) }
+
+#[tauri::command]
+fn stop_http() -> bool { stop_http_server() }
+
+#[tauri::command]
+fn set_linux_uinput(enabled: bool) -> Result<bool, String> { update_linux_uinput(enabled) }
+
+#[tauri::command]
+fn set_coalesce_taps(enabled: bool, threshold_ms: u64) -> bool { storage::update_coalesce_taps(enabled, threshold_ms) }
+
+#[tauri::command]
+fn set_test_mode(enabled: bool) -> bool { recorder::update_test_mode(enabled) }
+
+#[tauri::command]
+fn captured_output() -> Vec<String> { recorder::test_mode_output() }
+
+#[tauri::command]
+fn set_skip_leading_gap(enabled: bool) -> bool { recorder::update_skip_leading_gap(enabled) }
+
+#[tauri::command]
+fn set_record_only_app(bundle_id: Option<String>) -> Option<String> { recorder::update_record_only_app(bundle_id) }
+
+#[tauri::command]
+fn set_record_focus_changes(enabled: bool) -> bool { recorder::update_record_focus_changes(enabled) }
+
+#[tauri::command]
+fn set_high_precision_timing(enabled: bool) -> bool { recorder::update_high_precision_timing(enabled) }
+
+#[tauri::command]
+fn set_unicode_injection(enabled: bool) -> bool { recorder::update_unicode_injection(enabled) }
+
+#[tauri::command]
+fn set_mouse_transform(transform: Option<CoordTransform>) -> Result<Option<CoordTransform>, String> { recorder::update_mouse_transform(transform) }
+
+#[tauri::command]
+fn save_coord_preset(name: String, scale_x: f64, scale_y: f64, offset_x: f64, offset_y: f64) -> Result<(), String> {
+    save_coord_preset_named(&name, CoordTransform { scale_x, scale_y, offset_x, offset_y })
+}
+
+#[tauri::command]
+fn apply_coord_preset(name: String) -> Result<CoordTransform, String> { apply_coord_preset_named(&name) }
+
+#[tauri::command]
+fn list_coord_presets() -> BTreeMap<String, CoordTransform> { lock_or_recover(&COORD_PRESETS).clone() }
+
+#[tauri::command]
+fn set_mouse_coordinate_mode(mode: String) -> Result<String, String> { recorder::update_mouse_coordinate_mode(&mode) }
+
+#[tauri::command]
+fn set_mouse_interpolation(steps: u32) -> u32 { recorder::update_mouse_interpolation(steps) }
+
+#[tauri::command]
+fn set_mouse_safe_margin(px: u64) -> u64 { recorder::update_mouse_safe_margin(px) }
+
+#[tauri::command]
+fn set_min_modifier_hold_ms(ms: u64) -> u64 { recorder::update_min_modifier_hold_ms(ms) }
+
+#[tauri::command]
+fn set_modifier_settle_ms(ms: u64) -> u64 { recorder::update_modifier_settle_ms(ms) }
+
+#[tauri::command]
+fn set_playback_cpu_friendly(enabled: bool) -> bool { recorder::update_playback_cpu_friendly(enabled) }
+
+#[tauri::command]
+fn set_max_playback_runtime_ms(ms: u64) -> u64 { recorder::update_max_playback_runtime_ms(ms) }
+
+#[tauri::command]
+fn set_playback_priority(priority: String) -> Result<String, String> { recorder::update_playback_priority(&priority) }
+
+#[tauri::command]
+fn set_playback_move_threshold_px(px: f64) -> f64 { recorder::update_playback_move_threshold(px) }
+
+#[tauri::command]
+fn set_collapse_disabled_gaps(enabled: bool) -> bool { recorder::update_collapse_disabled_gaps(enabled) }
+
+#[tauri::command]
+fn set_preserve_doubleclicks(enabled: bool) -> bool { recorder::update_preserve_doubleclicks(enabled) }
+
+#[tauri::command]
+fn set_capture_media_keys(enabled: bool) -> bool { recorder::update_capture_media_keys(enabled) }
+
+#[tauri::command]
+fn set_key_blocklist(combos: Vec<String>) -> Result<usize, String> { recorder::update_key_blocklist(&combos) }
+
+#[tauri::command]
+fn set_danger_chords(combos: Vec<String>) -> Result<usize, String> { recorder::update_danger_chords(&combos) }
+
+#[tauri::command]
+fn set_timing_smoothing(window: usize) -> usize { recorder::update_timing_smoothing(window) }
+
+#[tauri::command]
+fn set_escape_aborts(enabled: bool) -> bool { recorder::update_escape_aborts(enabled) }
+
+#[tauri::command]
+fn set_notify_on_complete(enabled: bool) -> bool { update_notify_on_complete(enabled) }
+
+#[tauri::command]
+fn set_simulate_retries(retries: usize) -> usize { recorder::update_simulate_retries(retries) }
+
+#[tauri::command]
+fn simulate_retry_count() -> u64 { recorder::simulate_retries_used() }
+
 // MARK: Tauri app entry point
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -267,17 +855,165 @@ pub fn run() {
     tauri::Builder::default()
         .setup(|_app: &mut tauri::App| {
             let app_handle = _app.handle().clone();
+            *lock_or_recover(&APP_HANDLE) = Some(app_handle.clone());
+            load_coord_presets();
             let handles = init_setting(app_handle).expect("Failed to initialize settings");
-            *THREAD_HANDLES.lock().unwrap() = Some(handles);
+            *lock_or_recover(&THREAD_HANDLES) = Some(handles);
+            spawn_health_heartbeat();
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             start_record,
             stop_record,
             play_record,
-            keyboard_status
+            play_record_keyboard_only,
+            play_record_mouse_only,
+            play_record_fixed_key_gap,
+            play_record_window,
+            play_event,
+            simulate_combo,
+            step_mode_begin,
+            step_next,
+            step_reset,
+            start_playback,
+            stop_playback_by_id,
+            pause_playback,
+            resume_playback,
+            undo_last_playback,
+            panic_stop,
+            active_playbacks,
+            playback_eta_ms,
+            playback_position,
+            event_count,
+            gap_histogram,
+            play_playlist,
+            schedule_playback,
+            list_schedules,
+            cancel_schedule,
+            cancel_scheduled,
+            save_record,
+            save_slot,
+            snapshot_to_slot,
+            load_slot,
+            set_slot_tags,
+            search_slots,
+            add_slot_call,
+            remove_slot_calls,
+            add_branch,
+            remove_branches,
+            export_time_slice,
+            export_csv,
+            load_record,
+            set_stream_to_file,
+            recover_from_log,
+            remap_layout,
+            detect_layout,
+            build_from_keys,
+            split_record,
+            trim_after_last_key,
+            redact_range,
+            replace_key,
+            remap_displays,
+            get_tracks,
+            set_track_muted,
+            quantize_timing,
+            fit_duration,
+            repair_timing,
+            set_event_enabled,
+            undo,
+            redo,
+            get_events,
+            get_events_page,
+            set_event_label,
+            record_annotations,
+            extract_text,
+            privacy_scan,
+            set_privacy_scan_settings,
+            set_record_info,
+            get_record_info,
+            current_session_id,
+            diff_record,
+            scan_dangerous,
+            simulate_capabilities,
+            debug_state,
+            keyboard_status,
+            set_health_interval_ms,
+            current_hotkeys,
+            export_schema,
+            app_info,
+            input_devices,
+            set_input_device,
+            stuck_keys,
+            release_all_keys,
+            capture_screen,
+            set_verify_screenshot,
+            export_demo_gif,
+            start_http,
+            stop_http,
+            set_linux_uinput,
+            set_coalesce_taps,
+            set_test_mode,
+            captured_output,
+            set_skip_leading_gap,
+            set_record_focus_changes,
+            set_record_only_app,
+            set_high_precision_timing,
+            set_playback_cpu_friendly,
+            set_playback_priority,
+            set_max_playback_runtime_ms,
+            set_modifier_settle_ms,
+            set_min_modifier_hold_ms,
+            set_mouse_safe_margin,
+            set_mouse_interpolation,
+            set_mouse_coordinate_mode,
+            set_mouse_transform,
+            save_coord_preset,
+            apply_coord_preset,
+            list_coord_presets,
+            set_unicode_injection,
+            set_playback_move_threshold_px,
+            set_collapse_disabled_gaps,
+            set_key_blocklist,
+            set_capture_media_keys,
+            set_preserve_doubleclicks,
+            set_danger_chords,
+            set_timing_smoothing,
+            set_escape_aborts,
+            set_notify_on_complete,
+            set_simulate_retries,
+            simulate_retry_count
         ])
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn lock_or_recover_returns_data_after_poison() {
+
+        let mutex = Arc::new(Mutex::new(vec![1, 2]));
+        let poisoner = mutex.clone();
+
+        let result = std::thread::spawn(move || {
+            let mut values = poisoner.lock().unwrap();
+            values.push(3);
+            panic!("panic while holding the lock");
+        }).join();
+
+        assert!(result.is_err());
+        assert!(mutex.is_poisoned());
+
+        let mut values = lock_or_recover(&mutex);
+        assert_eq!(*values, vec![1, 2, 3]);
+        values.push(4);
+        drop(values);
+
+        assert_eq!(*lock_or_recover(&mutex), vec![1, 2, 3, 4]);
+    }
+}
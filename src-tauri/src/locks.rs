@@ -0,0 +1,55 @@
+//! 取得 Caps Lock / Num Lock 的目前狀態 (錄製時記錄，回放前切換成相同狀態)
+//! - Linux：以 `xset q` 查詢 (只支援 X11)
+//! - macOS、Windows 與 Wayland：尚未支援，不會記錄也不會切換
+
+use std::process::Command;
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+
+/// 鎖定鍵的開關狀態
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LockState {
+    pub caps_lock: bool,
+    pub num_lock: bool,
+}
+
+/// 取得目前鎖定鍵的開關狀態
+/// # 返回值
+/// - Option<LockState> - 無法取得時返回 None
+pub fn lock_state() -> Option<LockState> {
+
+    let output = lock_state_command()?.output().ok().filter(|output| output.status.success())?;
+    let text = String::from_utf8(output.stdout).ok()?;
+
+    Some(LockState { caps_lock: parse_flag(&text, "Caps Lock:")?, num_lock: parse_flag(&text, "Num Lock:")? })
+}
+
+/// 解析 `xset q` 輸出中指定名稱後面的 on / off
+/// # 參數
+/// - `text` - &str 輸出內容
+/// - `label` - &str 名稱 (例如 "Caps Lock:")
+/// # 返回值
+/// - Option<bool>
+fn parse_flag(text: &str, label: &str) -> Option<bool> {
+
+    let (_, rest) = text.split_once(label)?;
+
+    match rest.split_whitespace().next()? {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lock_state_command() -> Option<Command> {
+    let mut command = Command::new("xset");
+    command.arg("q");
+    Some(command)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn lock_state_command() -> Option<Command> {
+    None
+}
@@ -0,0 +1,38 @@
+//! 媒體鍵 (音量、播放控制) 的平台原始按鍵碼
+//! - rdev 沒有媒體鍵的 Key，這些按鍵以 `Key::Unknown(原始按鍵碼)` 記錄，回放時送出相同的原始按鍵碼 (只能在錄製的平台回放)
+//! - Windows：虛擬按鍵碼 (VK_VOLUME_MUTE 等)
+//! - Linux：X11 按鍵碼 (evdev 按鍵碼 + 8)，使用 uinput 時會換回 evdev 按鍵碼
+//! - macOS：媒體鍵與 Fn 鍵不會產生一般的按鍵事件，rdev 監聽不到，無法錄製
+
+use rdev::Key;
+
+/// 支援的媒體鍵 (名稱, 原始按鍵碼)
+#[cfg(target_os = "windows")]
+pub const MEDIA_KEYS: [(&str, u32); 7] = [
+    ("VolumeMute", 0xAD), ("VolumeDown", 0xAE), ("VolumeUp", 0xAF),
+    ("MediaNext", 0xB0), ("MediaPrevious", 0xB1), ("MediaStop", 0xB2), ("MediaPlayPause", 0xB3),
+];
+
+/// 支援的媒體鍵 (名稱, 原始按鍵碼)
+#[cfg(target_os = "linux")]
+pub const MEDIA_KEYS: [(&str, u32); 7] = [
+    ("VolumeMute", 121), ("VolumeDown", 122), ("VolumeUp", 123),
+    ("MediaNext", 171), ("MediaPlayPause", 172), ("MediaPrevious", 173), ("MediaStop", 174),
+];
+
+/// 支援的媒體鍵 (名稱, 原始按鍵碼)
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub const MEDIA_KEYS: [(&str, u32); 0] = [];
+
+/// 取得媒體鍵的名稱
+/// # 參數
+/// - `key` - Key
+/// # 返回值
+/// - Option<&'static str> - 不是支援的媒體鍵時返回 None
+pub fn media_key_name(key: Key) -> Option<&'static str> {
+
+    match key {
+        Key::Unknown(code) => MEDIA_KEYS.iter().find(|(_, media_code)| *media_code == code).map(|(name, _)| *name),
+        _ => None,
+    }
+}
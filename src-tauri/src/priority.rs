@@ -0,0 +1,28 @@
+//! 回放執行緒的優先權 (以 thread-priority 設定目前的執行緒，回放結束後還原)
+//! - Windows / macOS：一般使用者即可提高到最高的一般優先權
+//! - Linux：提高優先權需要 CAP_SYS_NICE 或調整 RLIMIT_NICE，一般使用者通常會失敗，失敗時只記錄錯誤、維持原本的優先權
+
+use thread_priority::{get_current_thread_priority, set_current_thread_priority, ThreadPriority};
+
+/// 提高目前執行緒的優先權
+/// # 返回值
+/// - Option<ThreadPriority> - 原本的優先權 (用來還原)，無法取得或沒有權限提高時返回 None
+pub fn raise_current_thread() -> Option<ThreadPriority> {
+
+    let original = get_current_thread_priority().map_err(|error| println!("[Warning] 無法取得執行緒的優先權: {:?}", error)).ok()?;
+
+    match set_current_thread_priority(ThreadPriority::Max) {
+        Ok(()) => Some(original),
+        Err(error) => { println!("[Warning] 無法提高回放執行緒的優先權: {:?}", error); None }
+    }
+}
+
+/// 還原目前執行緒的優先權
+/// # 參數
+/// - `original` - ThreadPriority 原本的優先權
+pub fn restore_current_thread(original: ThreadPriority) {
+
+    if let Err(error) = set_current_thread_priority(original) {
+        println!("[Warning] 無法還原回放執行緒的優先權: {:?}", error);
+    }
+}
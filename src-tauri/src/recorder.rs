@@ -0,0 +1,5021 @@
+//! 鍵盤錄製 / 回放的核心功能 (不依賴 Tauri 指令，可直接由其它 Rust 程式呼叫)
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::result::Result;
+use std::thread::{spawn, sleep, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use lazy_static::lazy_static;
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use rdev::{listen, simulate, display_size, Button, Event, EventType, Key, ListenError, SimulateError};
+use rdev::EventType::{KeyPress, KeyRelease, ButtonPress, ButtonRelease, MouseMove, Wheel};
+use global_hotkey::hotkey::{HotKey, Code, Modifiers};
+
+use crate::{emit_event, lock_or_recover, notify_complete, notify_playback_complete};
+use crate::storage::{StorageBackend, FileStorage, LogStorage, EventLog, RecordInfo, SlotCall, SlotBranch, CoordinateMode, EventTypeSchema};
+use crate::layout::{key_mapper, key_char};
+use crate::clock::{Clock, SystemClock};
+use crate::focus::frontmost_app;
+use crate::locks::{lock_state, LockState};
+use crate::text_input::type_text;
+use crate::media::media_key_name;
+use crate::priority::{raise_current_thread, restore_current_thread};
+
+/// 錄製下來的單一事件
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StoredEvent {
+    pub time: SystemTime,
+    #[schemars(with = "EventTypeSchema")]
+    pub event_type: EventType,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed: Option<Duration>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub double_click: bool, // 雙擊的第二次點擊 (含與第一次點擊之間的事件)，回放時縮短間隔
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>, // 按下時系統依目前的鍵盤配置產生的文字 (Unicode 輸入模式回放時送出)
+}
+
+impl StoredEvent {
+
+    /// 建立新的事件 (預設為啟用、沒有註解)
+    /// # 參數
+    /// - `time` - SystemTime 事件時間
+    /// - `event_type` - EventType
+    pub fn new(time: SystemTime, event_type: EventType) -> Self { StoredEvent { time, event_type, enabled: true, label: String::new(), elapsed: None, double_click: false, text: None } }
+
+    /// 與上一個事件之間的間隔 (兩者都有單調時間時以單調時間計算，不受錄製期間系統時間調整的影響)
+    /// # 參數
+    /// - `previous` - &StoredEvent 上一個事件
+    /// # 返回值
+    /// - Duration
+    pub fn gap_since(&self, previous: &StoredEvent) -> Duration {
+
+        match (self.elapsed, previous.elapsed) {
+            (Some(elapsed), Some(previous_elapsed)) => elapsed.saturating_sub(previous_elapsed),
+            _ => self.time.duration_since(previous.time).unwrap_or_default(),
+        }
+    }
+}
+
+fn default_enabled() -> bool { true }
+
+pub(crate) struct Recorder {
+    pub(crate) events: Vec<StoredEvent>,
+    pub(crate) started_at: Option<SystemTime>,
+    pub(crate) info: RecordInfo,
+    pub(crate) annotations: Vec<Annotation>,
+    started_monotonic: Option<Duration>, // 錄製開始時時鐘的單調時間
+    display_size: Option<(f64, f64)>,
+    muted_tracks: TrackMute,
+    undo_stack: Vec<EditSnapshot>,
+    redo_stack: Vec<EditSnapshot>,
+}
+
+/// 復原 / 重做保存的錄製內容 (事件、附加資訊與錄製開始時間一起保存，編輯只改其中一項時也能完整還原)
+#[derive(Clone)]
+struct EditSnapshot {
+    events: Vec<StoredEvent>,
+    info: RecordInfo,
+    started_at: Option<SystemTime>,
+}
+
+/// 錄製時記錄的註記 (例如前景應用程式的變化，回放時不會送出)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub index: usize, // 註記位於第幾個事件之前
+    pub time: SystemTime,
+    pub text: String,
+}
+
+/// 錄製時要擷取的事件種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureMask {
+    pub keyboard: bool,
+    pub mouse: bool,
+}
+
+impl CaptureMask {
+    pub const KEYBOARD: CaptureMask = CaptureMask { keyboard: true, mouse: false };
+    pub const ALL: CaptureMask = CaptureMask { keyboard: true, mouse: true };
+}
+
+impl Default for CaptureMask {
+    fn default() -> Self { CaptureMask::KEYBOARD }
+}
+
+/// 開始 / 停止錄製與回放的全局快速鍵
+#[derive(Debug, Clone)]
+pub struct HotkeyConfig {
+    pub record: HotKey,
+    pub playback: HotKey,
+    pub repeat: HotKey, // 以相同的方式重新執行上一次的回放
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        HotkeyConfig {
+            record: HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyS),
+            playback: HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyP),
+            repeat: HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyR),
+        }
+    }
+}
+
+/// 緊急停止的快速鍵 (固定註冊，無法更改)
+/// # 返回值
+/// - HotKey - Ctrl + Alt + Shift + Escape
+pub fn panic_hotkey() -> HotKey {
+    HotKey::new(Some(Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT), Code::Escape)
+}
+
+/// 每記錄一個事件時呼叫的 callback
+pub type RecordCallback = Box<dyn Fn(&Event) + Send>;
+
+/// 單一錄製器的狀態
+struct RecorderInner {
+    recorder: Mutex<Recorder>,
+    is_listening: AtomicBool,
+    is_paused: AtomicBool,
+    recording_id: AtomicU64,
+    stop_playback: Arc<AtomicBool>,
+    capture_mask: CaptureMask,
+    hotkeys: HotkeyConfig,
+    storage: Option<Box<dyn StorageBackend>>,
+    on_record: Mutex<Option<Arc<Mutex<RecordCallback>>>>,
+    clock: Arc<dyn Clock>,
+}
+
+/// 錄製器的 handle (可複製，複製後指向同一個錄製器)
+#[derive(Clone)]
+pub struct RecorderHandle {
+    inner: Arc<RecorderInner>,
+}
+
+/// 建立 RecorderHandle 的 builder
+#[derive(Default)]
+pub struct RecorderBuilder {
+    capture_mask: CaptureMask,
+    hotkeys: HotkeyConfig,
+    storage: Option<Box<dyn StorageBackend>>,
+    clock: Option<Arc<dyn Clock>>,
+}
+
+/// 播放清單的進度 (發送給前端)
+#[derive(Clone, Serialize)]
+struct PlaylistProgress {
+    index: usize,
+    total: usize,
+    path: String,
+}
+
+/// 修正事件時間順序的結果 (大幅重新排序時發送給前端)
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimingRepair {
+    reordered: usize,
+    adjusted: usize,
+    total: usize,
+}
+
+/// 尚未執行的排程回放
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleInfo {
+    pub id: u64,
+    pub epoch_ms: u64,        // 執行時間 (Unix 時間，毫秒)
+    pub slot: Option<String>, // 要回放的暫存錄製內容 (None 表示目前的錄製內容)
+}
+
+/// 回放時套用在滑鼠座標上的縮放與位移 (x * scale_x + offset_x，y 相同)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoordTransform {
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+impl CoordTransform {
+
+    /// 換算座標
+    /// # 參數
+    /// - `x` - f64
+    /// - `y` - f64
+    /// # 返回值
+    /// - (f64, f64)
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (x * self.scale_x + self.offset_x, y * self.scale_y + self.offset_y)
+    }
+
+    /// 檢查數值是否可以使用
+    /// # 返回值
+    /// - Result<(), String> - 數值不是有限值或縮放不大於 0 時返回錯誤
+    pub fn validate(&self) -> Result<(), String> {
+
+        let values = [self.scale_x, self.scale_y, self.offset_x, self.offset_y];
+        if values.iter().any(|value| !value.is_finite()) { return Err("座標換算的數值必須是有限值".to_string()); }
+        if self.scale_x <= 0.0 || self.scale_y <= 0.0 { return Err("縮放必須大於 0".to_string()); }
+
+        Ok(())
+    }
+}
+
+/// 螢幕在桌面上的位置與大小 (與滑鼠座標相同的單位)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl DisplayBounds {
+
+    /// 座標是否在螢幕範圍內
+    /// # 參數
+    /// - `x` - f64
+    /// - `y` - f64
+    /// # 返回值
+    /// - bool
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// 錄製器的內部狀態 (除錯與回報問題用)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugState {
+    pub is_listening: bool,
+    pub stop_playback: bool,
+    pub is_playing: bool,
+    pub is_playback_paused: bool,
+    pub event_count: usize,
+    pub playback_ids: Vec<u64>,
+    pub schedule_count: usize,
+    pub keyboard_thread_alive: bool,
+    pub last_listen_error: Option<String>,
+    pub simulate_retry_count: u64,
+    pub settings: DebugSettings,
+}
+
+/// 目前的設定值 (除錯與回報問題用)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugSettings {
+    pub test_mode: bool,
+    pub skip_leading_gap: bool,
+    pub simulate_retries: usize,
+    pub escape_aborts: bool,
+    pub timing_smoothing_window: usize,
+    pub collapse_disabled_gaps: bool,
+    pub record_focus_changes: bool,
+    pub record_only_app: Option<String>,
+    pub high_precision_timing: bool,
+    pub playback_move_threshold: f64,
+    pub playback_cpu_friendly: bool,
+    pub modifier_settle_ms: u64,
+    pub min_modifier_hold_ms: u64,
+    pub unicode_injection: bool,
+    pub mouse_safe_margin: u64,
+    pub mouse_interpolation_steps: u32,
+    pub normalized_mouse_coordinates: bool,
+    pub playback_priority: String,
+    pub mouse_transform: Option<CoordTransform>,
+    pub capture_media_keys: bool,
+    pub preserve_doubleclicks: bool,
+    pub coalesce_taps: bool,
+    pub tap_threshold_ms: u64,
+    pub max_playback_runtime_ms: u64,
+}
+
+/// 隱私檢查的判斷條件 (只是粗略的推測，不保證找出所有敏感內容)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacyScanSettings {
+    pub min_length: usize,           // 字串長度至少多少才檢查
+    pub min_char_classes: usize,     // 至少混合幾種字元 (小寫、大寫、數字、符號) 才視為疑似密碼
+    pub sensitive_apps: Vec<String>, // 前景應用程式名稱包含這些文字時 (不分大小寫)，之後的輸入視為疑似密碼
+}
+
+impl Default for PrivacyScanSettings {
+    fn default() -> Self {
+        let sensitive_apps = ["password", "keychain", "keepass", "bitwarden", "lastpass", "pinentry", "securityagent", "loginwindow", "credential"];
+        Self { min_length: 8, min_char_classes: 2, sensitive_apps: sensitive_apps.iter().map(|app| app.to_string()).collect() }
+    }
+}
+
+/// 隱私檢查發現的疑似敏感內容 (不包含原始文字)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyWarning {
+    pub kind: String,  // "suspicious-text" 疑似密碼的字串 / "sensitive-app" 在敏感的應用程式中輸入
+    pub message: String,
+    pub start_ms: u64, // 距離錄製開始的毫秒數 (可直接交給 redact_range)
+    pub end_ms: u64,
+}
+
+/// 隱私檢查的結果 (只是警告，不會阻止分享)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyReport {
+    pub text_length: usize, // 錄製內容會打出的文字長度
+    pub warnings: Vec<PrivacyWarning>,
+}
+
+/// 這個平台可以模擬的事件種類
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateCapabilities {
+    pub keyboard: bool,
+    pub mouse_move: bool,
+    pub mouse_button: bool,
+    pub wheel: bool,
+}
+
+impl SimulateCapabilities {
+
+    /// 是否可以模擬該事件
+    /// # 參數
+    /// - `event_type` - &EventType
+    /// # 返回值
+    /// - bool
+    pub fn supports(&self, event_type: &EventType) -> bool {
+
+        match event_type {
+            KeyPress(_) | KeyRelease(_) => self.keyboard,
+            MouseMove { .. } => self.mouse_move,
+            ButtonPress(_) | ButtonRelease(_) => self.mouse_button,
+            Wheel { .. } => self.wheel,
+        }
+    }
+}
+
+/// 錄製工作階段的事件內容 (RecordStarted / RecordStopped / RecordAborted / RecordPaused / RecordResumed)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordSessionEvent {
+    session_id: Option<String>,
+    event_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app: Option<String>,
+}
+
+/// 回放的結果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlaybackOutcome {
+    Empty,
+    Completed,
+    Stopped,
+    Failed,
+}
+
+/// 修飾鍵 (左右兩邊視為相同，只用於判斷組合鍵；錄製與回放的事件仍保留實際按下的那一邊)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    Meta,
+}
+
+/// 按鍵組合 (例如 `META+KeyQ`)
+#[derive(Debug, Clone)]
+struct KeyCombo {
+    name: String,
+    modifiers: Vec<Modifier>,
+    key: Key,
+}
+
+/// 錄製內容中找到的危險按鍵組合
+#[derive(Debug, Clone, Serialize)]
+pub struct DangerFinding {
+    pub index: usize,
+    pub description: String,
+}
+
+/// 比較兩個錄製內容時的差異種類
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// 比較兩個錄製內容時的單一差異
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffEntry {
+    pub kind: DiffKind,
+    pub index: Option<usize>,          // 目前錄製內容的位置
+    pub baseline_index: Option<usize>, // 比較檔案的位置
+    pub description: String,
+}
+
+/// 比較錄製內容時，滑鼠移動預設可容許的座標距離
+const DIFF_POSITION_TOLERANCE: f64 = 2.0;
+
+/// 比較錄製內容時最多建立的對齊表格大小 (去掉相同的開頭與結尾後，兩邊事件數量的乘積)
+const MAX_DIFF_CELLS: usize = 20_000_000;
+
+/// 預設的危險按鍵組合
+const DEFAULT_DANGER_CHORDS: [&str; 5] = ["META+KeyQ", "META+KeyW", "CTRL+ALT+Delete", "ALT+F4", "CTRL+KeyW"];
+
+/// 回放時要送出的事件種類 (略過的事件依 `update_collapse_disabled_gaps()` 的設定保留或略過等待時間)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaybackFilter {
+    #[default]
+    All,
+    KeyboardOnly,
+    MouseOnly,
+}
+
+/// 單次回放的選項
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlaybackOptions {
+    pub filter: PlaybackFilter,
+    pub fixed_key_gap: Option<Duration>, // 鍵盤事件之間改用固定的間隔 (滑鼠事件維持原本的時間)
+    pub muted: TrackMute,
+}
+
+/// 一頁錄製的事件 (事件很多時分頁取得，避免一次傳送全部的事件)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPage {
+    pub offset: usize, // 第一個事件的位置
+    pub total: usize,  // 全部的事件數量
+    pub events: Vec<StoredEvent>,
+}
+
+/// 回放時靜音的軌道 (錄製內容仍是單一的事件列表，鍵盤與滑鼠事件各自視為一個軌道)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackMute {
+    pub keyboard: bool,
+    pub mouse: bool,
+}
+
+impl TrackMute {
+
+    /// 該事件是否屬於靜音的軌道
+    /// # 參數
+    /// - `event_type` - &EventType
+    /// # 返回值
+    /// - bool
+    fn mutes(&self, event_type: &EventType) -> bool {
+        match event_type {
+            KeyPress(_) | KeyRelease(_) => self.keyboard,
+            ButtonPress(_) | ButtonRelease(_) | MouseMove { .. } | Wheel { .. } => self.mouse,
+        }
+    }
+}
+
+/// 軌道中的事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackEvent {
+    pub index: usize,   // 在錄製內容中的位置 (可直接用於其它以位置編輯的指令)
+    pub offset_ms: u64, // 距離錄製開始的毫秒數 (兩個軌道共用同一個時間軸)
+    pub event: StoredEvent,
+}
+
+/// 單一軌道
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Track {
+    pub muted: bool,
+    pub events: Vec<TrackEvent>,
+}
+
+/// 以鍵盤與滑鼠兩個軌道呈現的錄製內容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tracks {
+    pub keyboard: Track,
+    pub mouse: Track,
+}
+
+impl PlaybackFilter {
+
+    /// 是否要送出該事件
+    /// # 參數
+    /// - `event_type` - &EventType
+    /// # 返回值
+    /// - bool
+    fn includes(&self, event_type: &EventType) -> bool {
+        match self {
+            PlaybackFilter::All => true,
+            PlaybackFilter::KeyboardOnly => matches!(event_type, KeyPress(_) | KeyRelease(_)),
+            PlaybackFilter::MouseOnly => matches!(event_type, ButtonPress(_) | ButtonRelease(_) | MouseMove { .. } | Wheel { .. }),
+        }
+    }
+}
+
+/// 上一次回放的方式 (重複回放時以相同的參數執行)
+#[derive(Debug, Clone)]
+enum LastPlayback {
+    Record(PlaybackOptions),
+    Window(u64, u64),
+    Spawn,
+    Slot(String),
+    Playlist(Vec<String>, u64),
+}
+
+/// 暫存在記憶體中的錄製內容
+#[derive(Clone)]
+struct Slot {
+    events: Vec<StoredEvent>,
+    started_at: Option<SystemTime>,
+    info: RecordInfo,
+}
+
+/// 逐步回放的進度
+struct StepSession {
+    events: Vec<StoredEvent>,
+    index: usize,
+}
+
+/// 單一回放的控制資訊
+pub(crate) struct Playback {
+    events: Vec<StoredEvent>,
+    started_at: Option<SystemTime>,
+    index: AtomicUsize,
+    stop: Arc<AtomicBool>, // 這次回放自己的停止訊號 (播放清單的每個檔案與分支共用同一個)
+    recorder_stop: Arc<AtomicBool>,
+    held_keys: Mutex<Vec<Key>>,
+    clock: Arc<dyn Clock>,
+    options: PlaybackOptions,
+    branches: Vec<(usize, SlotBranch)>, // (展開後的事件位置, 分支)
+}
+
+/// 復原 / 重做最多保留的步數
+const MAX_HISTORY: usize = 32;
+
+/// 高精度計時時，最後以忙碌等待補足的時間
+const SPIN_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// 送出的模擬事件在這段時間內被監聽到時，視為自己送出的事件
+const SYNTHETIC_MATCH_WINDOW: Duration = Duration::from_millis(200);
+
+/// 降低 CPU 使用時，連續多少個沒有間隔的事件後短暫休息
+const CPU_FRIENDLY_BURST: usize = 32;
+
+static TEST_MODE: AtomicBool = AtomicBool::new(false);
+static NEXT_PLAYBACK_ID: AtomicU64 = AtomicU64::new(1);
+static NEXT_SCHEDULE_ID: AtomicU64 = AtomicU64::new(1);
+static SKIP_LEADING_GAP: AtomicBool = AtomicBool::new(true);
+static SIMULATE_RETRIES: AtomicUsize = AtomicUsize::new(2);
+static SIMULATE_RETRY_COUNT: AtomicU64 = AtomicU64::new(0);
+static ESCAPE_ABORTS: AtomicBool = AtomicBool::new(false);
+static TIMING_SMOOTHING_WINDOW: AtomicUsize = AtomicUsize::new(0);
+static COLLAPSE_DISABLED_GAPS: AtomicBool = AtomicBool::new(false);
+static RECORD_FOCUS_CHANGES: AtomicBool = AtomicBool::new(false);
+static HIGH_PRECISION_TIMING: AtomicBool = AtomicBool::new(false);
+static PLAYBACK_MOVE_THRESHOLD_BITS: AtomicU64 = AtomicU64::new(0); // f64 (像素) 的 bits
+static PLAYBACK_CPU_FRIENDLY: AtomicBool = AtomicBool::new(false);
+static MODIFIER_SETTLE_MS: AtomicU64 = AtomicU64::new(5);
+static UNICODE_INJECTION: AtomicBool = AtomicBool::new(false);
+static MOUSE_SAFE_MARGIN: AtomicU64 = AtomicU64::new(0);
+static PLAYBACK_PAUSED: AtomicBool = AtomicBool::new(false);
+static CAPTURE_MEDIA_KEYS: AtomicBool = AtomicBool::new(true);
+static MOUSE_INTERPOLATION_STEPS: AtomicU32 = AtomicU32::new(0);
+static MIN_MODIFIER_HOLD_MS: AtomicU64 = AtomicU64::new(0);
+static NORMALIZED_MOUSE_COORDINATES: AtomicBool = AtomicBool::new(false);
+static PRESERVE_DOUBLECLICKS: AtomicBool = AtomicBool::new(true);
+static PLAYBACK_HIGH_PRIORITY: AtomicBool = AtomicBool::new(false);
+static IS_REPEATING: AtomicBool = AtomicBool::new(false);
+static MAX_PLAYBACK_RUNTIME_MS: AtomicU64 = AtomicU64::new(0);
+
+/// 判斷雙擊的時間與距離 (rdev 無法取得系統的雙擊設定，以各平台的預設值為準)
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+const DOUBLE_CLICK_DISTANCE: f64 = 4.0;
+
+/// 回放雙擊時每個事件之間最長的間隔 (遠小於系統的雙擊時間)
+const DOUBLE_CLICK_STEP: Duration = Duration::from_millis(40);
+
+/// 遮蔽後的按鍵與標籤 (不對應任何實體按鍵，事件同時停用、回放時不會送出)
+const REDACTED_KEY: Key = Key::Unknown(0);
+const REDACTED_LABEL: &str = "已遮蔽";
+
+// 用於存儲所有錄製器的全局變數 (監聽執行緒會把事件分派給每一個錄製器)
+lazy_static! {
+    static ref HANDLES: Arc<Mutex<Vec<Weak<RecorderInner>>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+// Tauri 指令使用的預設錄製器
+lazy_static! {
+    static ref DEFAULT_RECORDER: RecorderHandle = RecorderBuilder::default().build();
+}
+
+// 用於存儲進行中回放的全局變數 (以回放編號為 key)
+lazy_static! {
+    static ref PLAYBACKS: Arc<Mutex<HashMap<u64, Arc<Playback>>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+// 用於存儲排程回放與取消訊號的全局變數 (以排程編號對應)
+lazy_static! {
+    static ref SCHEDULED: Arc<Mutex<HashMap<u64, (ScheduleInfo, Arc<AtomicBool>)>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+// 用於存儲目前認定為按住中按鍵的全局變數 (監聽與回放都會更新)
+lazy_static! {
+    static ref HELD_KEYS: Arc<Mutex<Vec<Key>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+// 用於存儲這個平台可以模擬的事件種類 (第一次使用時偵測)
+lazy_static! {
+    static ref SIMULATE_CAPABILITIES: SimulateCapabilities = detect_simulate_capabilities();
+}
+
+// 用於存儲即時寫入的事件記錄檔的全局變數
+lazy_static! {
+    static ref STREAM_LOG: Arc<Mutex<Option<EventLog>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲最近送出的模擬事件的全局變數 (錄製時略過自己送出的事件)
+lazy_static! {
+    static ref SYNTHETIC_EVENTS: Arc<Mutex<VecDeque<(Instant, EventType)>>> = Arc::new(Mutex::new(VecDeque::new()));
+}
+
+// 用於存儲逐步回放進度的全局變數
+lazy_static! {
+    static ref STEP_SESSION: Arc<Mutex<Option<StepSession>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲已命名錄製內容的全局變數 (以名稱為 key)
+lazy_static! {
+    static ref SLOTS: Arc<Mutex<HashMap<String, Slot>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+// 用於存儲上一次回放打出的字元數的全局變數 (不是單純打字時為 None)
+lazy_static! {
+    static ref LAST_PLAYBACK_TYPED: Arc<Mutex<Option<usize>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲只錄製哪個前景應用程式的全局變數
+lazy_static! {
+    static ref RECORD_ONLY_APP: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲回放時滑鼠座標換算設定的全局變數 (None 表示不換算)
+lazy_static! {
+    static ref MOUSE_TRANSFORM: Arc<Mutex<Option<CoordTransform>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲按下時沒有錄製的按鍵的全局變數 (放開時同樣不錄製)
+lazy_static! {
+    static ref SKIPPED_PRESSES: Arc<Mutex<Vec<Key>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+// 用於存儲上一次回放方式的全局變數
+lazy_static! {
+    static ref LAST_PLAYBACK: Arc<Mutex<Option<LastPlayback>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲隱私檢查判斷條件的全局變數
+lazy_static! {
+    static ref PRIVACY_SCAN_SETTINGS: Arc<Mutex<PrivacyScanSettings>> = Arc::new(Mutex::new(PrivacyScanSettings::default()));
+}
+
+// 用於存儲鍵盤監聽執行緒最後一次錯誤的全局變數
+lazy_static! {
+    static ref LAST_LISTEN_ERROR: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+}
+
+// 用於存儲不錄製的按鍵組合的全局變數
+lazy_static! {
+    static ref KEY_BLOCKLIST: Arc<Mutex<Vec<KeyCombo>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+// 用於存儲危險按鍵組合的全局變數
+lazy_static! {
+    static ref DANGER_CHORDS: Arc<Mutex<Vec<KeyCombo>>> = Arc::new(Mutex::new(
+        DEFAULT_DANGER_CHORDS.iter().filter_map(|combo| parse_combo(combo).ok()).collect()
+    ));
+}
+
+// 用於存儲測試模式下模擬輸出的全局變數
+lazy_static! {
+    static ref CAPTURED_OUTPUT: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+}
+
+/// 取得預設錄製器
+/// # 返回值
+/// - &'static RecorderHandle
+pub fn default_handle() -> &'static RecorderHandle {
+    &DEFAULT_RECORDER
+}
+
+// MARK: RecorderBuilder
+impl RecorderBuilder {
+
+    /// 設定開始 / 停止錄製與回放的快速鍵
+    /// # 參數
+    /// - `record` - HotKey 開始 / 停止錄製
+    /// - `playback` - HotKey 執行回放
+    pub fn hotkeys(mut self, record: HotKey, playback: HotKey) -> Self {
+        self.hotkeys = HotkeyConfig { record, playback, repeat: self.hotkeys.repeat };
+        self
+    }
+
+    /// 設定重複上一次回放的快速鍵
+    /// # 參數
+    /// - `repeat` - HotKey
+    pub fn repeat_hotkey(mut self, repeat: HotKey) -> Self {
+        self.hotkeys.repeat = repeat;
+        self
+    }
+
+    /// 設定錄製時要擷取的事件種類
+    /// # 參數
+    /// - `capture_mask` - CaptureMask
+    pub fn capture_mask(mut self, capture_mask: CaptureMask) -> Self {
+        self.capture_mask = capture_mask;
+        self
+    }
+
+    /// 設定 `save()` / `load()` 使用的儲存方式
+    /// # 參數
+    /// - `storage` - impl StorageBackend
+    pub fn storage(mut self, storage: impl StorageBackend + 'static) -> Self {
+        self.storage = Some(Box::new(storage));
+        self
+    }
+
+    /// 設定錄製 / 回放使用的時間來源 (預設為系統時鐘)
+    /// # 參數
+    /// - `clock` - impl Clock
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// 建立錄製器 (建立後即會收到監聽執行緒分派的事件)
+    /// # 返回值
+    /// - RecorderHandle
+    pub fn build(self) -> RecorderHandle {
+
+        let inner = Arc::new(RecorderInner {
+            recorder: Mutex::new(Recorder { events: Vec::new(), started_at: None, info: RecordInfo::default(), annotations: Vec::new(), started_monotonic: None, display_size: None, muted_tracks: TrackMute::default(), undo_stack: Vec::new(), redo_stack: Vec::new() }),
+            is_listening: AtomicBool::new(false),
+            is_paused: AtomicBool::new(false),
+            recording_id: AtomicU64::new(0),
+            stop_playback: Arc::new(AtomicBool::new(true)),
+            capture_mask: self.capture_mask,
+            hotkeys: self.hotkeys,
+            storage: self.storage,
+            on_record: Mutex::new(None),
+            clock: self.clock.unwrap_or_else(|| Arc::new(SystemClock)),
+        });
+
+        lock_or_recover(&HANDLES).push(Arc::downgrade(&inner));
+        RecorderHandle { inner }
+    }
+}
+
+// MARK: RecorderHandle
+impl RecorderHandle {
+
+    /// 建立 RecorderBuilder
+    /// # 返回值
+    /// - RecorderBuilder
+    pub fn builder() -> RecorderBuilder { RecorderBuilder::default() }
+
+    /// 開始錄製 (會清除先前的錄製內容)
+    /// # 返回值
+    /// - bool - 返回當前是否在錄製狀態
+    pub fn start(&self) -> bool {
+        self.inner.stop_playback.store(false, Ordering::SeqCst);
+        self.inner.is_listening.store(true, Ordering::SeqCst);
+
+        let lock_state = lock_state();
+        let display_size = if NORMALIZED_MOUSE_COORDINATES.load(Ordering::SeqCst) { current_display_size() } else { None };
+        let coordinate_mode = if display_size.is_some() { CoordinateMode::Normalized } else { CoordinateMode::Pixels };
+        let session_id = new_session_id();
+        let mut recorder = self.lock();
+        recorder.events.clear();
+        recorder.started_at = Some(self.inner.clock.now());
+        recorder.started_monotonic = Some(self.inner.clock.monotonic());
+        recorder.display_size = display_size;
+        recorder.info = RecordInfo { lock_state, session_id: Some(session_id.clone()), coordinate_mode, ..RecordInfo::default() };
+        recorder.annotations.clear();
+        recorder.undo_stack.clear();
+        recorder.redo_stack.clear();
+        drop(recorder);
+
+        truncate_stream();
+        self.inner.is_paused.store(false, Ordering::SeqCst);
+        let recording_id = self.inner.recording_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let is_app_scoped = lock_or_recover(&RECORD_ONLY_APP).is_some();
+        if RECORD_FOCUS_CHANGES.load(Ordering::SeqCst) || is_app_scoped { spawn_focus_watcher(self.inner.clone(), recording_id); }
+
+        emit_event("RecordStarted", RecordSessionEvent { session_id: Some(session_id), event_count: 0, app: None });
+
+        self.inner.is_listening.load(Ordering::SeqCst)
+    }
+
+    /// 停止錄製 (同時停止該錄製器的回放)
+    /// # 參數
+    /// - `is_hotkey` - bool 是否通過熱鍵停止錄製
+    /// # 返回值
+    /// - bool - 返回當前是否仍在錄製狀態
+    pub fn stop(&self, is_hotkey: bool) -> bool {
+        self.inner.stop_playback.store(true, Ordering::SeqCst);
+        self.inner.is_listening.store(false, Ordering::SeqCst);
+
+        if is_hotkey {
+            let mut recorder = self.lock();
+            // Pop last 3 events if possible
+            for _ in 0..3 {
+                if recorder.events.is_empty() { break; }
+                recorder.events.pop();
+            }
+        }
+
+        emit_event("RecordStopped", session_event_of(&self.inner, None));
+        notify_complete("錄製已停止");
+
+        self.inner.is_listening.load(Ordering::SeqCst)
+    }
+
+    /// 回放錄製的事件 (等待回放結束)
+    /// # 返回值
+    /// - PlaybackOutcome - 沒有可回放的事件 / 回放完成 / 中途被停止
+    pub fn play(&self) -> PlaybackOutcome { self.play_with(PlaybackOptions::default()) }
+
+    /// 依指定的選項回放錄製的事件 (等待回放結束)
+    /// # 參數
+    /// - `options` - PlaybackOptions
+    /// # 返回值
+    /// - PlaybackOutcome - 沒有可回放的事件 / 回放完成 / 中途被停止 / 展開呼叫的錄製內容失敗
+    pub fn play_with(&self, options: PlaybackOptions) -> PlaybackOutcome {
+
+        let Some((events, started_at, branches)) = self.playback_events() else { return PlaybackOutcome::Failed; };
+        if events.is_empty() { emit_event("empty-recording", 0); return PlaybackOutcome::Empty; }
+
+        self.inner.stop_playback.store(false, Ordering::SeqCst);
+
+        let options = PlaybackOptions { muted: self.lock().muted_tracks, ..options };
+        let original_locks = sync_lock_state(self.lock().info.lock_state);
+        let stop = Arc::new(AtomicBool::new(false));
+        let is_finished = start_runtime_watchdog(stop.clone());
+        let (id, playback) = register_playback(events, started_at, stop, self.inner.stop_playback.clone(), self.inner.clock.clone(), options, branches);
+        let is_stop = run_playback(&playback);
+        is_finished.store(true, Ordering::SeqCst);
+        unregister_playback(id);
+        restore_lock_state(original_locks);
+        notify_playback_complete(is_stop);
+
+        if is_stop { PlaybackOutcome::Stopped } else { PlaybackOutcome::Completed }
+    }
+
+    /// 只回放距離錄製開始指定時間範圍內的事件 (等待回放結束；範圍內的第一個事件立即送出，不展開呼叫的暫存內容與分支)
+    /// # 參數
+    /// - `start_ms` - u64 開始時間 (毫秒，含)
+    /// - `end_ms` - u64 結束時間 (毫秒，含)
+    /// # 返回值
+    /// - bool - 範圍不正確或範圍內沒有事件時返回 false
+    pub fn play_window(&self, start_ms: u64, end_ms: u64) -> bool {
+
+        if start_ms > end_ms { return false; }
+
+        let (events, started_at) = self.recorded_events();
+        let Some(origin) = started_at.or_else(|| events.first().map(|event| event.time)) else { return false; };
+        let info = self.record_info();
+
+        let window: Vec<StoredEvent> = events.into_iter().filter(|event| (start_ms..=end_ms).contains(&offset_ms_of(event, Some(origin)))).collect();
+        if window.is_empty() { return false; }
+
+        let window = match to_pixels(window, info.coordinate_mode) {
+            Ok(window) => window,
+            Err(error) => { println!("[Error] {}", error); return false; }
+        };
+
+        self.inner.stop_playback.store(false, Ordering::SeqCst);
+
+        let original_locks = sync_lock_state(info.lock_state);
+        let options = PlaybackOptions { muted: self.lock().muted_tracks, ..Default::default() };
+        let stop = Arc::new(AtomicBool::new(false));
+        let is_finished = start_runtime_watchdog(stop.clone());
+        let (id, playback) = register_playback(window, None, stop, self.inner.stop_playback.clone(), self.inner.clock.clone(), options, Vec::new());
+        let is_stop = run_playback(&playback);
+        is_finished.store(true, Ordering::SeqCst);
+        unregister_playback(id);
+        restore_lock_state(original_locks);
+        notify_playback_complete(is_stop);
+
+        true
+    }
+
+    /// 在新執行緒回放錄製的事件 (不等待回放結束，可同時進行多個回放)
+    /// # 返回值
+    /// - Option<u64> - 回放編號，沒有可回放的事件時返回 None
+    pub fn spawn_play(&self) -> Option<u64> {
+
+        let (events, started_at, branches) = self.playback_events()?;
+        if events.is_empty() { emit_event("empty-recording", 0); return None; }
+
+        self.inner.stop_playback.store(false, Ordering::SeqCst);
+
+        let options = PlaybackOptions { muted: self.lock().muted_tracks, ..Default::default() };
+        let (id, playback) = register_playback(events, started_at, Arc::new(AtomicBool::new(false)), self.inner.stop_playback.clone(), self.inner.clock.clone(), options, branches);
+        let recorded_locks = self.lock().info.lock_state;
+
+        spawn(move || {
+            let original_locks = sync_lock_state(recorded_locks);
+            let is_finished = start_runtime_watchdog(playback.stop.clone());
+            let is_stop = run_playback(&playback);
+            is_finished.store(true, Ordering::SeqCst);
+            unregister_playback(id);
+            restore_lock_state(original_locks);
+            notify_playback_complete(is_stop);
+        });
+
+        Some(id)
+    }
+
+    /// 取得目前錄製的所有事件
+    /// # 返回值
+    /// - Vec<StoredEvent>
+    pub fn events(&self) -> Vec<StoredEvent> { self.lock().events.clone() }
+
+    /// 取得一頁錄製的事件 (位置超出範圍時返回空的一頁)
+    /// # 參數
+    /// - `offset` - usize 第一個事件的位置
+    /// - `limit` - usize 最多幾個事件
+    /// # 返回值
+    /// - EventPage
+    pub fn events_page(&self, offset: usize, limit: usize) -> EventPage {
+
+        let recorder = self.lock();
+        let total = recorder.events.len();
+        let start = offset.min(total);
+        let end = start.saturating_add(limit).min(total);
+
+        EventPage { offset: start, total, events: recorder.events[start..end].to_vec() }
+    }
+
+    /// 取得錄製時記錄的註記
+    /// # 返回值
+    /// - Vec<Annotation>
+    pub fn annotations(&self) -> Vec<Annotation> { self.lock().annotations.clone() }
+
+    /// 還原錄製內容會打出的文字 (以錄製開始時的 Caps Lock 狀態為起點)
+    /// # 返回值
+    /// - String
+    pub fn extract_text(&self) -> String {
+        let recorder = self.lock();
+        typed_text(&recorder.events, recorder.info.lock_state.is_some_and(|state| state.caps_lock))
+    }
+
+    /// 檢查錄製內容是否可能包含密碼等敏感的輸入 (粗略的推測，只返回警告)
+    /// # 返回值
+    /// - PrivacyReport
+    pub fn privacy_scan(&self) -> PrivacyReport {
+
+        let text = self.extract_text();
+        let settings = lock_or_recover(&PRIVACY_SCAN_SETTINGS).clone();
+        let recorder = self.lock();
+        let origin = recorder.started_at.or_else(|| recorder.events.first().map(|event| event.time));
+        let is_caps_lock = recorder.info.lock_state.is_some_and(|state| state.caps_lock);
+
+        let mut warnings = suspicious_text_warnings(&recorder.events, is_caps_lock, origin, &settings);
+        warnings.extend(sensitive_app_warnings(&recorder.events, &recorder.annotations, origin, &settings));
+        warnings.sort_by_key(|warning| warning.start_ms);
+
+        PrivacyReport { text_length: text.chars().count(), warnings }
+    }
+
+    /// 目前錄製內容的工作階段編號 (載入舊的錄製檔案時為 None)
+    /// # 返回值
+    /// - Option<String>
+    pub fn session_id(&self) -> Option<String> { self.lock().info.session_id.clone() }
+
+    /// 取得錄製內容的附加資訊 (名稱、說明、標籤)
+    /// # 返回值
+    /// - RecordInfo
+    pub fn record_info(&self) -> RecordInfo { self.lock().info.clone() }
+
+    /// 設定錄製內容的顯示名稱與說明 (與檔名 / 暫存名稱無關，會隨錄製檔案一起儲存)
+    /// # 參數
+    /// - `name` - String 名稱
+    /// - `description` - String 說明
+    pub fn set_record_info(&self, name: String, description: String) {
+        let mut recorder = self.lock();
+        recorder.save_history();
+        recorder.info.name = name;
+        recorder.info.description = description;
+    }
+
+    /// 是否正在錄製
+    /// # 返回值
+    /// - bool
+    pub fn is_recording(&self) -> bool { self.inner.is_listening.load(Ordering::SeqCst) }
+
+    /// 設定每記錄一個事件時呼叫的 callback (呼叫時不會持有錄製內容的鎖)
+    /// # 參數
+    /// - `callback` - RecordCallback
+    pub fn set_on_record(&self, callback: RecordCallback) {
+        *lock_or_recover(&self.inner.on_record) = Some(Arc::new(Mutex::new(callback)));
+    }
+
+    /// 移除記錄事件時呼叫的 callback
+    pub fn clear_on_record(&self) {
+        *lock_or_recover(&self.inner.on_record) = None;
+    }
+
+    /// 取得設定的快速鍵
+    /// # 返回值
+    /// - HotkeyConfig
+    pub fn hotkeys(&self) -> HotkeyConfig { self.inner.hotkeys.clone() }
+
+    /// 取得設定的擷取種類
+    /// # 返回值
+    /// - CaptureMask
+    pub fn capture_mask(&self) -> CaptureMask { self.inner.capture_mask }
+
+    /// 以 builder 設定的儲存方式儲存錄製內容
+    /// # 返回值
+    /// - Result<(), String> - 沒有設定儲存方式時返回錯誤
+    pub fn save(&self) -> Result<(), String> {
+        let storage = self.inner.storage.as_deref().ok_or_else(|| "沒有設定儲存方式".to_string())?;
+        self.save_to(storage)
+    }
+
+    /// 以 builder 設定的儲存方式載入錄製內容
+    /// # 返回值
+    /// - Result<usize, String> - 載入的事件數量，沒有設定儲存方式時返回錯誤
+    pub fn load(&self) -> Result<usize, String> {
+        let storage = self.inner.storage.as_deref().ok_or_else(|| "沒有設定儲存方式".to_string())?;
+        self.load_from(storage)
+    }
+
+    /// 以指定的儲存方式儲存錄製內容
+    /// # 參數
+    /// - `storage` - &dyn StorageBackend
+    /// # 返回值
+    /// - Result<(), String>
+    pub fn save_to(&self, storage: &dyn StorageBackend) -> Result<(), String> {
+        let (events, started_at) = self.recorded_events();
+        let info = self.lock().info.clone();
+        storage.save_with_info(&events, started_at, &info)
+    }
+
+    /// 以指定的儲存方式儲存一段時間範圍內的錄製內容 (時間以範圍的開頭為起點重新計算)
+    /// # 參數
+    /// - `start_ms` - u64 範圍開始 (距離錄製開始的毫秒數)
+    /// - `end_ms` - u64 範圍結束 (超過錄製長度時以錄製長度為準)
+    /// - `storage` - &dyn StorageBackend
+    /// # 返回值
+    /// - Result<usize, String> - 儲存的事件數量，範圍不正確時返回錯誤
+    pub fn save_time_slice_to(&self, start_ms: u64, end_ms: u64, storage: &dyn StorageBackend) -> Result<usize, String> {
+
+        let (events, started_at) = self.recorded_events();
+        let origin = started_at.or_else(|| events.first().map(|event| event.time)).ok_or_else(|| "沒有錄製內容".to_string())?;
+
+        // 有單調時間時以單調時間計算，錄製期間系統時間被調整也不會切錯範圍
+        let offset_ms = |event: &StoredEvent| offset_ms_of(event, Some(origin));
+        let duration_ms = events.last().map(offset_ms).unwrap_or_default();
+
+        if start_ms >= end_ms { return Err(format!("範圍不正確: {} - {}", start_ms, end_ms)); }
+        if start_ms > duration_ms { return Err(format!("範圍超出錄製長度: {} > {}", start_ms, duration_ms)); }
+
+        let slice_start = UNIX_EPOCH;
+        let slice: Vec<StoredEvent> = events.iter()
+            .filter(|event| (start_ms..=end_ms).contains(&offset_ms(*event)))
+            .map(|event| StoredEvent { time: slice_start + Duration::from_millis(offset_ms(event) - start_ms), elapsed: None, ..event.clone() })
+            .collect();
+
+        let info = self.lock().info.clone();
+        storage.save_with_info(&slice, Some(slice_start), &info)?;
+        Ok(slice.len())
+    }
+
+    /// 以指定的儲存方式載入錄製內容，取代目前的錄製內容
+    /// # 參數
+    /// - `storage` - &dyn StorageBackend
+    /// # 返回值
+    /// - Result<usize, String> - 載入的事件數量
+    pub fn load_from(&self, storage: &dyn StorageBackend) -> Result<usize, String> {
+
+        let (events, started_at, info) = storage.load_with_info()?;
+        let count = events.len();
+
+        let mut recorder = self.lock();
+        recorder.save_history();
+        recorder.events = events;
+        recorder.started_at = Some(started_at);
+        recorder.info = info;
+
+        Ok(count)
+    }
+
+    /// 將目前的錄製內容以指定名稱暫存在記憶體中 (同名時覆蓋)
+    /// # 參數
+    /// - `name` - &str 名稱
+    /// # 返回值
+    /// - usize - 暫存的事件數量
+    pub fn store_slot(&self, name: &str) -> usize {
+
+        let recorder = self.lock();
+        let slot = Slot { events: recorder.events.clone(), started_at: recorder.started_at, info: recorder.info.clone() };
+        drop(recorder);
+
+        let count = slot.events.len();
+        lock_or_recover(&SLOTS).insert(name.to_string(), slot);
+
+        count
+    }
+
+    /// 把目前的錄製內容複製成快照暫存起來 (不寫入檔案，之後可以用 restore_slot 換回來)
+    /// # 參數
+    /// - `name` - &str 名稱
+    /// - `overwrite` - bool 名稱已存在時是否覆蓋
+    /// # 返回值
+    /// - Result<usize, String> - 暫存的事件數量，名稱為空或已存在且不覆蓋時返回錯誤
+    pub fn snapshot_to_slot(&self, name: &str, overwrite: bool) -> Result<usize, String> {
+
+        let name = name.trim();
+        if name.is_empty() { return Err("名稱不能為空".to_string()); }
+        if !overwrite && lock_or_recover(&SLOTS).contains_key(name) { return Err(format!("錄製內容已存在: {}", name)); }
+
+        Ok(self.store_slot(name))
+    }
+
+    /// 以暫存的錄製內容取代目前的錄製內容
+    /// # 參數
+    /// - `name` - &str 名稱
+    /// # 返回值
+    /// - Result<usize, String> - 載入的事件數量，找不到該名稱時返回錯誤
+    pub fn restore_slot(&self, name: &str) -> Result<usize, String> {
+
+        let slot = lock_or_recover(&SLOTS).get(name).cloned().ok_or_else(|| format!("找不到錄製內容: {}", name))?;
+        let count = slot.events.len();
+
+        let mut recorder = self.lock();
+        recorder.save_history();
+        recorder.events = slot.events;
+        recorder.started_at = slot.started_at;
+        recorder.info = slot.info;
+
+        Ok(count)
+    }
+
+    /// 將錄製的按鍵從一種鍵盤配置換成另一種 (直接修改錄製內容)
+    /// # 參數
+    /// - `from` - &str 錄製時的配置名稱
+    /// - `to` - &str 回放時的配置名稱
+    /// # 返回值
+    /// - Result<(), String> - 未知的配置名稱時返回錯誤
+    pub fn remap_layout(&self, from: &str, to: &str) -> Result<(), String> {
+
+        let mapper = key_mapper(from, to)?;
+        let mut recorder = self.lock();
+        recorder.save_history();
+
+        for event in recorder.events.iter_mut() {
+            event.event_type = match event.event_type {
+                KeyPress(key) => KeyPress(mapper(key)),
+                KeyRelease(key) => KeyRelease(mapper(key)),
+                event_type => event_type,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// 以按鍵名稱清單建立錄製內容 (每個按鍵產生按下 / 放開兩個事件，取代目前的錄製內容)
+    /// # 參數
+    /// - `keys` - &[String] 按鍵名稱 (例如 `KeyA`、`Return`)
+    /// - `gap_ms` - u64 每個事件之間的間隔（毫秒）
+    /// # 返回值
+    /// - Result<usize, String> - 建立的事件數量，有無法辨識的按鍵名稱時返回錯誤
+    pub fn build_from_keys(&self, keys: &[String], gap_ms: u64) -> Result<usize, String> {
+
+        let keys = keys.iter().map(|name| parse_key(name)).collect::<Result<Vec<Key>, String>>()?;
+
+        let started_at = self.inner.clock.now();
+        let gap = Duration::from_millis(gap_ms);
+        let mut time = started_at;
+        let mut events = Vec::with_capacity(keys.len() * 2);
+
+        for key in keys {
+            time += gap;
+            events.push(StoredEvent::new(time, KeyPress(key)));
+            time += gap;
+            events.push(StoredEvent::new(time, KeyRelease(key)));
+        }
+
+        let count = events.len();
+
+        let mut recorder = self.lock();
+        recorder.save_history();
+        recorder.events = events;
+        recorder.started_at = Some(started_at);
+
+        Ok(count)
+    }
+
+    /// 將錄製內容從指定位置分成兩段，移除並返回後半段
+    /// # 參數
+    /// - `index` - usize 後半段的第一個事件位置
+    /// # 返回值
+    /// - Result<Vec<StoredEvent>, String> - 後半段的事件 (時間以 UNIX_EPOCH 為起點重新計算)，位置超出範圍時返回錯誤
+    /// - 位置落在後半段的呼叫與分支會移除 (見 drop_dangling_links)
+    pub fn split(&self, index: usize) -> Result<Vec<StoredEvent>, String> {
+
+        let mut recorder = self.lock();
+        if index >= recorder.events.len() { return Err(format!("位置超出範圍: {} (共 {} 個事件)", index, recorder.events.len())); }
+
+        recorder.save_history();
+
+        let tail = recorder.events.split_off(index);
+        let first_time = tail[0].time;
+        recorder.drop_dangling_links();
+
+        Ok(tail.into_iter().map(|event| StoredEvent {
+            time: UNIX_EPOCH + event.time.duration_since(first_time).unwrap_or_default(),
+            elapsed: None,
+            ..event
+        }).collect())
+    }
+
+    /// 啟用 / 停用單一事件 (停用的事件保留在錄製內容中，但回放時不會送出)
+    /// # 參數
+    /// - `index` - usize 事件位置
+    /// - `enabled` - bool 是否啟用
+    /// # 返回值
+    /// - Result<(), String> - 位置超出範圍時返回錯誤
+    pub fn set_event_enabled(&self, index: usize, enabled: bool) -> Result<(), String> {
+
+        let mut recorder = self.lock();
+        if index >= recorder.events.len() { return Err(format!("位置超出範圍: {} (共 {} 個事件)", index, recorder.events.len())); }
+
+        recorder.save_history();
+        recorder.events[index].enabled = enabled;
+
+        Ok(())
+    }
+
+    /// 移除最後一個鍵盤事件之後的所有事件 (例如按停止熱鍵前的滑鼠移動；其餘事件的時間不變)
+    /// # 返回值
+    /// - usize - 移除的事件數量 (沒有鍵盤事件時不移除)
+    /// - 位置落在移除範圍的呼叫與分支會一起移除 (見 drop_dangling_links)
+    pub fn trim_after_last_key(&self) -> usize {
+
+        let mut recorder = self.lock();
+        let Some(last_key) = recorder.events.iter().rposition(|event| matches!(event.event_type, KeyPress(_) | KeyRelease(_))) else { return 0; };
+
+        let count = recorder.events.len() - last_key - 1;
+        if count == 0 { return 0; }
+
+        recorder.save_history();
+        recorder.events.truncate(last_key + 1);
+        recorder.drop_dangling_links();
+
+        count
+    }
+
+    /// 遮蔽時間範圍內的按鍵 (換成停用的未知按鍵並加上標籤，事件的位置與時間不變，儲存或匯出的內容不會留下原本的按鍵)
+    /// - 範圍內按下、範圍外才放開的按鍵，放開事件也會遮蔽；範圍外按下的按鍵，範圍內的放開事件保留，避免回放時按鍵卡住
+    /// - 啟用「略過停用事件的間隔」時，遮蔽的事件一樣會略過間隔
+    /// - 只修改記憶體中的錄製內容；錄製時若有即時寫入記錄檔 (set_stream_to_file)，記錄檔仍保留原本的按鍵，需要另外刪除
+    /// # 參數
+    /// - `start_ms` - u64 開始時間 (距離錄製開始的毫秒數)
+    /// - `end_ms` - u64 結束時間 (距離錄製開始的毫秒數，包含)
+    /// # 返回值
+    /// - Result<usize, String> - 遮蔽的事件數量，開始時間晚於結束時間時返回錯誤
+    pub fn redact_range(&self, start_ms: u64, end_ms: u64) -> Result<usize, String> {
+
+        if start_ms > end_ms { return Err(format!("開始時間晚於結束時間: {} > {}", start_ms, end_ms)); }
+
+        let mut recorder = self.lock();
+        let origin = recorder.started_at.or_else(|| recorder.events.first().map(|event| event.time));
+
+        let mut pending: Vec<Key> = Vec::new();
+        let mut indices: Vec<usize> = Vec::new();
+
+        for (index, event) in recorder.events.iter().enumerate() {
+
+            let is_inside = (start_ms..=end_ms).contains(&offset_ms_of(event, origin));
+
+            match event.event_type {
+                KeyPress(key) if is_inside => { pending.push(key); indices.push(index); }
+                KeyRelease(key) if pending.contains(&key) => { pending.retain(|held| *held != key); indices.push(index); }
+                _ => {}
+            }
+        }
+
+        if indices.is_empty() { return Ok(0); }
+
+        recorder.save_history();
+
+        for index in indices.iter() {
+            let event = &mut recorder.events[*index];
+            event.event_type = match event.event_type { KeyRelease(_) => KeyRelease(REDACTED_KEY), _ => KeyPress(REDACTED_KEY) };
+            event.enabled = false;
+            event.label = REDACTED_LABEL.to_string();
+            event.text = None;
+        }
+
+        Ok(indices.len())
+    }
+
+    /// 把所有使用指定按鍵的鍵盤事件 (按下與放開) 改成另一個按鍵 (直接修改錄製內容，時間不變；回放時的按鍵對應不會修改錄製內容)
+    /// # 參數
+    /// - `from` - Key 原本的按鍵
+    /// - `to` - Key 新的按鍵
+    /// # 返回值
+    /// - usize - 修改的事件數量
+    pub fn replace_key(&self, from: Key, to: Key) -> usize {
+
+        if from == to { return 0; }
+
+        let mut recorder = self.lock();
+        let count = recorder.events.iter().filter(|event| matches!(event.event_type, KeyPress(key) | KeyRelease(key) if key == from)).count();
+        if count == 0 { return 0; }
+
+        recorder.save_history();
+
+        for event in recorder.events.iter_mut() {
+            match event.event_type {
+                KeyPress(key) if key == from => { event.event_type = KeyPress(to); event.text = None; }
+                KeyRelease(key) if key == from => event.event_type = KeyRelease(to),
+                _ => {}
+            }
+        }
+
+        count
+    }
+
+    /// 把位於來源螢幕上的滑鼠座標換算到目標螢幕的相同相對位置 (直接修改錄製內容，其它螢幕上的座標不變)
+    /// # 參數
+    /// - `from` - DisplayBounds 來源螢幕
+    /// - `to` - DisplayBounds 目標螢幕
+    /// # 返回值
+    /// - Result<usize, String> - 換算的事件數量，螢幕大小不正確或錄製內容以比例記錄時返回錯誤
+    pub fn remap_display(&self, from: DisplayBounds, to: DisplayBounds) -> Result<usize, String> {
+
+        if from.width <= 0.0 || from.height <= 0.0 || to.width <= 0.0 || to.height <= 0.0 { return Err("螢幕大小必須大於 0".to_string()); }
+
+        let mut recorder = self.lock();
+        if recorder.info.coordinate_mode == CoordinateMode::Normalized { return Err("以比例記錄的錄製內容無法換算螢幕".to_string()); }
+
+        let count = recorder.events.iter().filter(|event| matches!(event.event_type, MouseMove { x, y } if from.contains(x, y))).count();
+        if count == 0 || from == to { return Ok(0); }
+
+        recorder.save_history();
+
+        for event in recorder.events.iter_mut() {
+            if let MouseMove { x, y } = event.event_type {
+                if !from.contains(x, y) { continue; }
+                event.event_type = MouseMove { x: to.x + (x - from.x) * to.width / from.width, y: to.y + (y - from.y) * to.height / from.height };
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// 把錄製內容輸出成 CSV (分析輸入的節奏用，每行一個事件：offset_ns,event_type,key,button,x,y)
+    /// - 錄製時以時鐘的單調時間記錄距離開始的奈秒數；從版本 4 之前的檔案載入的事件沒有這個資訊，改以毫秒精度的事件時間計算
+    /// - 滾輪事件的 x,y 為捲動量；以比例記錄的滑鼠座標輸出原本的比例
+    /// # 參數
+    /// - `path` - &str 檔案路徑
+    /// # 返回值
+    /// - Result<usize, String> - 輸出的事件數量，寫入失敗時返回錯誤
+    pub fn export_csv(&self, path: &str) -> Result<usize, String> {
+
+        let recorder = self.lock();
+        let origin = recorder.started_at.or_else(|| recorder.events.first().map(|event| event.time));
+        let mut csv = String::from("offset_ns,event_type,key,button,x,y\n");
+
+        for event in recorder.events.iter() {
+
+            let offset = event.elapsed.unwrap_or_else(|| origin.and_then(|origin| event.time.duration_since(origin).ok()).unwrap_or_default());
+            let fields = match event.event_type {
+                KeyPress(key) => format!("KeyPress,{:?},,,", key),
+                KeyRelease(key) => format!("KeyRelease,{:?},,,", key),
+                ButtonPress(button) => format!("ButtonPress,,{:?},,", button),
+                ButtonRelease(button) => format!("ButtonRelease,,{:?},,", button),
+                MouseMove { x, y } => format!("MouseMove,,,{},{}", x, y),
+                Wheel { delta_x, delta_y } => format!("Wheel,,,{},{}", delta_x, delta_y),
+            };
+
+            csv.push_str(&format!("{},{}\n", offset.as_nanos(), fields));
+        }
+
+        let count = recorder.events.len();
+        drop(recorder);
+
+        std::fs::write(path, csv).map_err(|error| error.to_string())?;
+        Ok(count)
+    }
+
+    /// 以鍵盤與滑鼠兩個軌道取得錄製內容 (只是呈現方式，底層仍是同一個事件列表)
+    /// # 返回值
+    /// - Tracks
+    pub fn tracks(&self) -> Tracks {
+
+        let recorder = self.lock();
+        let origin = recorder.started_at.or_else(|| recorder.events.first().map(|event| event.time));
+        let muted = recorder.muted_tracks;
+
+        let (keyboard, mouse): (Vec<TrackEvent>, Vec<TrackEvent>) = recorder.events.iter().enumerate()
+            .map(|(index, event)| TrackEvent { index, offset_ms: offset_ms_of(event, origin), event: event.clone() })
+            .partition(|track_event| matches!(track_event.event.event_type, KeyPress(_) | KeyRelease(_)));
+
+        Tracks { keyboard: Track { muted: muted.keyboard, events: keyboard }, mouse: Track { muted: muted.mouse, events: mouse } }
+    }
+
+    /// 設定回放時是否略過某個軌道的事件 (只影響目前錄製內容的回放，不修改事件)
+    /// # 參數
+    /// - `track` - &str "keyboard" 或 "mouse"
+    /// - `muted` - bool
+    /// # 返回值
+    /// - Result<TrackMute, String> - 返回當前設定，未知的軌道時返回錯誤
+    pub fn set_track_muted(&self, track: &str, muted: bool) -> Result<TrackMute, String> {
+
+        let mut recorder = self.lock();
+
+        match track.to_ascii_lowercase().as_str() {
+            "keyboard" => recorder.muted_tracks.keyboard = muted,
+            "mouse" => recorder.muted_tracks.mouse = muted,
+            _ => return Err(format!("未知的軌道: {}", track)),
+        }
+
+        Ok(recorder.muted_tracks)
+    }
+
+    /// 把每個事件與前一個事件的間隔四捨五入成指定毫秒數的倍數 (直接修改錄製內容，第一個事件的時間不變)
+    /// # 參數
+    /// - `grid_ms` - u64 間隔的單位 (毫秒)
+    /// # 返回值
+    /// - Result<usize, String> - 間隔有改變的事件數量，單位為 0 時返回錯誤
+    pub fn quantize_timing(&self, grid_ms: u64) -> Result<usize, String> {
+
+        if grid_ms == 0 { return Err("間隔的單位必須大於 0".to_string()); }
+
+        let grid = Duration::from_millis(grid_ms).as_nanos();
+        let mut recorder = self.lock();
+        if recorder.events.len() < 2 { return Ok(0); }
+
+        let gaps: Vec<Duration> = recorder.events.windows(2).map(|pair| pair[1].gap_since(&pair[0])).collect();
+        let quantized: Vec<Duration> = gaps.iter().map(|gap| {
+            let nanos = (gap.as_nanos() + grid / 2) / grid * grid;
+            Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+        }).collect();
+
+        let count = gaps.iter().zip(quantized.iter()).filter(|(gap, quantized)| gap != quantized).count();
+        if count == 0 { return Ok(0); }
+
+        recorder.save_history();
+
+        for (index, gap) in quantized.into_iter().enumerate() {
+            let previous = recorder.events[index].clone();
+            let event = &mut recorder.events[index + 1];
+            event.time = previous.time + gap;
+            event.elapsed = previous.elapsed.map(|elapsed| elapsed + gap);
+        }
+
+        Ok(count)
+    }
+
+    /// 依比例調整所有事件之間的間隔，讓錄製內容的總長度 (第一個到最後一個事件) 變成指定的毫秒數 (直接修改錄製內容，第一個事件的時間不變)
+    /// - 原本所有事件都在同一時間時，改為平均分配間隔
+    /// # 參數
+    /// - `target_ms` - u64 目標總長度 (毫秒)
+    /// # 返回值
+    /// - Result<u64, String> - 原本的總長度 (毫秒)，事件少於兩個時返回錯誤
+    pub fn fit_duration(&self, target_ms: u64) -> Result<u64, String> {
+
+        let mut recorder = self.lock();
+        if recorder.events.len() < 2 { return Err("至少需要兩個事件才能調整總長度".to_string()); }
+
+        let gaps: Vec<Duration> = recorder.events.windows(2).map(|pair| pair[1].gap_since(&pair[0])).collect();
+        let total: Duration = gaps.iter().sum();
+        let target = Duration::from_millis(target_ms);
+
+        let fitted: Vec<Duration> = if total.is_zero() {
+            vec![target / gaps.len() as u32; gaps.len()]
+        } else {
+            let ratio = target.as_secs_f64() / total.as_secs_f64();
+            gaps.iter().map(|gap| gap.mul_f64(ratio)).collect()
+        };
+
+        recorder.save_history();
+
+        for (index, gap) in fitted.into_iter().enumerate() {
+            let previous = recorder.events[index].clone();
+            let event = &mut recorder.events[index + 1];
+            event.time = previous.time + gap;
+            event.elapsed = previous.elapsed.map(|elapsed| elapsed + gap);
+        }
+
+        Ok(total.as_millis() as u64)
+    }
+
+    /// 依時間重新排序事件 (穩定排序) 並把倒退的單調時間改為與前一個事件相同 (直接修改錄製內容)
+    /// - 有單調時間的事件依單調時間排序 (與 gap_since 相同，不受錄製期間系統時間調整的影響)，沒有的才依與錄製開始時間的差距排序
+    /// - 超過一成的事件需要重新排序時，發送 "TimingRepairWarning" 事件並記錄警告 (可能是檔案損壞)
+    /// - 呼叫暫存內容與分支的位置不會跟著調整
+    /// # 返回值
+    /// - usize - 重新排序或調整時間的事件數量
+    pub fn repair_timing(&self) -> usize {
+
+        let mut recorder = self.lock();
+
+        let origin = recorder.started_at.or_else(|| recorder.events.iter().map(|event| event.time).min());
+        let offset = |event: &StoredEvent| event.elapsed.unwrap_or_else(|| origin.and_then(|origin| event.time.duration_since(origin).ok()).unwrap_or_default());
+
+        let mut order: Vec<usize> = (0..recorder.events.len()).collect();
+        order.sort_by_key(|index| offset(&recorder.events[*index]));
+        let reordered = order.iter().enumerate().filter(|(position, index)| position != *index).count();
+
+        let mut elapsed_max: Option<Duration> = None;
+        let mut adjusted = 0;
+
+        for index in order.iter() {
+            let Some(elapsed) = recorder.events[*index].elapsed else { continue; };
+            if elapsed_max.is_some_and(|maximum| elapsed < maximum) { adjusted += 1; } else { elapsed_max = Some(elapsed); }
+        }
+
+        if reordered == 0 && adjusted == 0 { return 0; }
+
+        recorder.save_history();
+
+        let events = std::mem::take(&mut recorder.events);
+        let mut sorted: Vec<StoredEvent> = order.iter().map(|index| events[*index].clone()).collect();
+        let mut elapsed_max: Option<Duration> = None;
+
+        for event in sorted.iter_mut() {
+            if let Some(elapsed) = event.elapsed {
+                let elapsed = elapsed_max.map_or(elapsed, |maximum| elapsed.max(maximum));
+                event.elapsed = Some(elapsed);
+                elapsed_max = Some(elapsed);
+            }
+        }
+
+        let total = sorted.len();
+        recorder.events = sorted;
+        drop(recorder);
+
+        if reordered * 10 > total {
+            println!("[Warning] 重新排序了 {} / {} 個事件的時間順序，錄製檔案可能已損壞", reordered, total);
+            emit_event("TimingRepairWarning", TimingRepair { reordered, adjusted, total });
+        }
+
+        reordered + adjusted
+    }
+
+    /// 設定單一事件的註解 (只用於顯示，回放時會忽略)
+    /// # 參數
+    /// - `index` - usize 事件位置
+    /// - `label` - String 註解 (空字串表示清除)
+    /// # 返回值
+    /// - Result<(), String> - 位置超出範圍時返回錯誤
+    pub fn set_event_label(&self, index: usize, label: String) -> Result<(), String> {
+
+        let mut recorder = self.lock();
+        if index >= recorder.events.len() { return Err(format!("位置超出範圍: {} (共 {} 個事件)", index, recorder.events.len())); }
+
+        recorder.save_history();
+        recorder.events[index].label = label.trim().to_string();
+
+        Ok(())
+    }
+
+    /// 復原上一次的編輯
+    /// # 返回值
+    /// - bool - 有可復原的編輯時返回 true
+    pub fn undo(&self) -> bool {
+
+        let mut recorder = self.lock();
+        let Some(snapshot) = recorder.undo_stack.pop() else { return false; };
+
+        let current = recorder.restore(snapshot);
+        recorder.redo_stack.push(current);
+
+        true
+    }
+
+    /// 重做上一次被復原的編輯
+    /// # 返回值
+    /// - bool - 有可重做的編輯時返回 true
+    pub fn redo(&self) -> bool {
+
+        let mut recorder = self.lock();
+        let Some(snapshot) = recorder.redo_stack.pop() else { return false; };
+
+        let current = recorder.restore(snapshot);
+        recorder.undo_stack.push(current);
+
+        true
+    }
+
+    /// 取得錄製事件的互斥鎖
+    /// # 返回值
+    /// - MutexGuard<Recorder>
+    pub(crate) fn lock(&self) -> MutexGuard<'_, Recorder> {
+        lock_or_recover(&self.inner.recorder)
+    }
+
+    /// 取得目前錄製的事件 (複製一份供回放使用)
+    /// # 返回值
+    /// - (Vec<StoredEvent>, Option<SystemTime>) - 錄製的事件與錄製開始的時間
+    fn recorded_events(&self) -> (Vec<StoredEvent>, Option<SystemTime>) {
+        let recorder = self.lock();
+        (recorder.events.clone(), recorder.started_at)
+    }
+
+    /// 取得要回放的事件 (已展開呼叫的暫存錄製內容；失敗時發送 "SlotCallFailed" 事件)
+    /// # 返回值
+    /// - Option<(Vec<StoredEvent>, Option<SystemTime>, Vec<(usize, SlotBranch)>)> - 事件、錄製開始時間、分支 (展開後的事件位置)
+    fn playback_events(&self) -> Option<(Vec<StoredEvent>, Option<SystemTime>, Vec<(usize, SlotBranch)>)> {
+
+        let (events, started_at) = self.recorded_events();
+        let info = self.lock().info.clone();
+        let expanded = to_pixels(events, info.coordinate_mode).and_then(|events| expand_slot_calls_with_positions(events, &info.calls, &mut Vec::new()));
+
+        match expanded {
+            Ok((events, positions)) => {
+                let branches = info.branches.into_iter().filter_map(|branch| Some((*positions.get(branch.index)?, branch))).collect();
+                Some((events, started_at, branches))
+            }
+            Err(error) => { emit_event("SlotCallFailed", error); None }
+        }
+    }
+
+    /// 在指定的事件之前呼叫另一個暫存的錄製內容 (回放時展開，被呼叫的內容之後的事件會往後延)
+    /// # 參數
+    /// - `index` - usize 事件位置 (等於事件數量時表示放在最後)
+    /// - `name` - &str 暫存的名稱
+    /// # 返回值
+    /// - Result<(), String> - 位置超出範圍或找不到該名稱時返回錯誤
+    pub fn add_slot_call(&self, index: usize, name: &str) -> Result<(), String> {
+
+        if !lock_or_recover(&SLOTS).contains_key(name) { return Err(format!("找不到錄製內容: {}", name)); }
+
+        let mut recorder = self.lock();
+        if index > recorder.events.len() { return Err(format!("位置超出範圍: {} (共 {} 個事件)", index, recorder.events.len())); }
+
+        recorder.save_history();
+        recorder.info.calls.push(SlotCall { index, name: name.to_string() });
+        recorder.info.calls.sort_by_key(|call| call.index);
+
+        Ok(())
+    }
+
+    /// 移除指定位置的暫存錄製內容呼叫
+    /// # 參數
+    /// - `index` - usize 事件位置
+    /// # 返回值
+    /// - usize - 移除的數量
+    pub fn remove_slot_calls(&self, index: usize) -> usize {
+
+        let mut recorder = self.lock();
+        let count = recorder.info.calls.iter().filter(|call| call.index == index).count();
+        if count == 0 { return 0; }
+
+        recorder.save_history();
+        recorder.info.calls.retain(|call| call.index != index);
+
+        count
+    }
+
+    /// 在指定的事件之前加入分支：回放到這裡時依按鍵是否按住，選擇回放其中一個暫存的錄製內容
+    /// # 參數
+    /// - `index` - usize 事件位置 (等於事件數量時表示放在最後)
+    /// - `key` - Key 要檢查的按鍵
+    /// - `then_slot` - Option<String> 按住時回放的暫存名稱
+    /// - `else_slot` - Option<String> 沒有按住時回放的暫存名稱
+    /// # 返回值
+    /// - Result<(), String> - 位置超出範圍或找不到暫存名稱時返回錯誤
+    pub fn add_branch(&self, index: usize, key: Key, then_slot: Option<String>, else_slot: Option<String>) -> Result<(), String> {
+
+        let slots = lock_or_recover(&SLOTS);
+        if let Some(name) = then_slot.iter().chain(else_slot.iter()).find(|name| !slots.contains_key(*name)) { return Err(format!("找不到錄製內容: {}", name)); }
+        drop(slots);
+
+        let mut recorder = self.lock();
+        if index > recorder.events.len() { return Err(format!("位置超出範圍: {} (共 {} 個事件)", index, recorder.events.len())); }
+
+        recorder.save_history();
+        recorder.info.branches.push(SlotBranch { index, key, then_slot, else_slot });
+        recorder.info.branches.sort_by_key(|branch| branch.index);
+
+        Ok(())
+    }
+
+    /// 移除指定位置的分支
+    /// # 參數
+    /// - `index` - usize 事件位置
+    /// # 返回值
+    /// - usize - 移除的數量
+    pub fn remove_branches(&self, index: usize) -> usize {
+
+        let mut recorder = self.lock();
+        let count = recorder.info.branches.iter().filter(|branch| branch.index == index).count();
+        if count == 0 { return 0; }
+
+        recorder.save_history();
+        recorder.info.branches.retain(|branch| branch.index != index);
+
+        count
+    }
+}
+
+impl Recorder {
+
+    /// 在編輯錄製內容之前保存目前的內容 (供復原使用，會清除可重做的內容)
+    pub(crate) fn save_history(&mut self) {
+
+        let snapshot = EditSnapshot { events: self.events.clone(), info: self.info.clone(), started_at: self.started_at };
+        self.undo_stack.push(snapshot);
+        self.redo_stack.clear();
+
+        if self.undo_stack.len() > MAX_HISTORY { self.undo_stack.remove(0); }
+    }
+
+    /// 移除位置超出事件數量的暫存錄製內容呼叫與分支 (事件被截短之後)，有移除時分別發送 "SlotCallsDropped" / "BranchesDropped" 事件 (移除的數量)
+    /// - 位置等於事件數量的呼叫與分支仍然有效 (放在最後)
+    fn drop_dangling_links(&mut self) {
+
+        let count = self.events.len();
+        let (call_count, branch_count) = (self.info.calls.len(), self.info.branches.len());
+        self.info.calls.retain(|call| call.index <= count);
+        self.info.branches.retain(|branch| branch.index <= count);
+
+        let dropped_calls = call_count - self.info.calls.len();
+        let dropped_branches = branch_count - self.info.branches.len();
+        if dropped_calls > 0 { emit_event("SlotCallsDropped", dropped_calls); }
+        if dropped_branches > 0 { emit_event("BranchesDropped", dropped_branches); }
+    }
+
+    /// 還原保存的錄製內容
+    /// # 參數
+    /// - `snapshot` - EditSnapshot 要還原的內容
+    /// # 返回值
+    /// - EditSnapshot - 還原前的內容
+    fn restore(&mut self, snapshot: EditSnapshot) -> EditSnapshot {
+        EditSnapshot {
+            events: std::mem::replace(&mut self.events, snapshot.events),
+            info: std::mem::replace(&mut self.info, snapshot.info),
+            started_at: std::mem::replace(&mut self.started_at, snapshot.started_at),
+        }
+    }
+}
+
+/// 將按鍵名稱轉成 Key (名稱與 rdev 的 Key 相同，例如 `KeyA`、`ShiftLeft`；單一英文字母或數字可省略前綴，例如 `T`、`1`)
+/// # 參數
+/// - `name` - &str 按鍵名稱
+/// # 返回值
+/// - Result<Key, String> - 無法辨識時返回錯誤
+pub(crate) fn parse_key(name: &str) -> Result<Key, String> {
+
+    let full_name = match name.as_bytes() {
+        [letter] if letter.is_ascii_alphabetic() => format!("Key{}", letter.to_ascii_uppercase() as char),
+        [digit] if digit.is_ascii_digit() => format!("Num{}", *digit as char),
+        _ => name.to_string(),
+    };
+
+    serde_json::from_value(serde_json::Value::String(full_name)).map_err(|_| format!("無法辨識的按鍵名稱: {}", name))
+}
+
+/// 記錄事件值
+/// # 參數
+/// - `inner` - &RecorderInner 要記錄的錄製器
+/// - `event` - &Event
+fn record_into(inner: &RecorderInner, event: &Event) {
+
+    let Some(stored) = store_into(inner, event.time, event.event_type, event_text(event)) else { return; };
+
+    append_to_stream(&stored);
+
+    // 先取出 callback 再呼叫，callback 內可以再呼叫錄製器的功能
+    let on_record = lock_or_recover(&inner.on_record).clone();
+    if let Some(on_record) = on_record {
+        let callback = lock_or_recover(&on_record);
+        (**callback)(event);
+    }
+}
+
+/// 把事件加入錄製器 (不在錄製中、已暫停或不在擷取種類內時不記錄)
+/// # 參數
+/// - `inner` - &RecorderInner 要記錄的錄製器
+/// - `time` - SystemTime 事件時間
+/// - `event_type` - EventType
+/// - `text` - Option<String> 按下按鍵時產生的文字 (只記錄在按下的事件)
+/// # 返回值
+/// - Option<StoredEvent> - 記錄下來的事件
+fn store_into(inner: &RecorderInner, time: SystemTime, event_type: EventType, text: Option<String>) -> Option<StoredEvent> {
+
+    if !inner.is_listening.load(Ordering::SeqCst) || inner.is_paused.load(Ordering::SeqCst) { return None; }
+
+    let is_captured = match event_type {
+        KeyPress(_) | KeyRelease(_) => inner.capture_mask.keyboard,
+        MouseMove { .. } | Wheel { .. } | ButtonPress(_) | ButtonRelease(_) => inner.capture_mask.mouse,
+    };
+    if !is_captured { return None; }
+
+    // 先取得時間再等待鎖，避免其它執行緒佔用錄製內容時影響時間的精確度
+    let now = inner.clock.monotonic();
+    let mut recorder = lock_or_recover(&inner.recorder);
+    let elapsed = recorder.started_monotonic.map(|started| now.saturating_sub(started));
+    let event_type = match (event_type, recorder.display_size) {
+        (MouseMove { x, y }, Some((width, height))) => MouseMove { x: x / width, y: y / height },
+        (event_type, _) => event_type,
+    };
+    let text = text.filter(|_| matches!(event_type, KeyPress(_)));
+    let mut stored = StoredEvent { elapsed, text, ..StoredEvent::new(time, event_type) };
+
+    if let ButtonPress(button) = event_type {
+        let scale = recorder.display_size.unwrap_or((1.0, 1.0));
+        if let Some(first) = double_click_start(&recorder.events, button, &stored, scale).filter(|_| PRESERVE_DOUBLECLICKS.load(Ordering::SeqCst)) {
+            for previous in recorder.events[first + 1..].iter_mut() { previous.double_click = true; }
+            stored.double_click = true;
+        }
+    }
+
+    recorder.events.push(stored.clone());
+
+    Some(stored)
+}
+
+/// 取得事件產生的文字 (fufesou/rdev 放在 Event.unicode；上游 rdev 為 Event.name)
+/// - 死鍵與控制字元 (Enter、Backspace 等) 不算文字，回放時照常逐鍵模擬
+/// # 參數
+/// - `event` - &Event
+/// # 返回值
+/// - Option<String>
+fn event_text(event: &Event) -> Option<String> {
+    event.unicode.as_ref()
+        .filter(|unicode| !unicode.is_dead)
+        .and_then(|unicode| unicode.name.clone())
+        .filter(|text| !text.is_empty() && !text.chars().any(char::is_control))
+}
+
+/// 檢查滑鼠按下是否為雙擊的第二次點擊 (同一個按鍵在雙擊時間內、中間沒有移動超過雙擊距離)
+/// # 參數
+/// - `events` - &[StoredEvent] 已錄製的事件
+/// - `button` - Button 這次按下的按鍵
+/// - `press` - &StoredEvent 這次按下的事件
+/// - `scale` - (f64, f64) 座標換算成像素的比例 (以比例記錄座標時為螢幕大小)
+/// # 返回值
+/// - Option<usize> - 第一次點擊的位置，不是雙擊時返回 None
+fn double_click_start(events: &[StoredEvent], button: Button, press: &StoredEvent, scale: (f64, f64)) -> Option<usize> {
+
+    let first = events.iter().rposition(|event| matches!(event.event_type, ButtonPress(_)))?;
+    if events[first].event_type != ButtonPress(button) || press.gap_since(&events[first]) > DOUBLE_CLICK_TIME { return None; }
+
+    let position = |event: &StoredEvent| match event.event_type { MouseMove { x, y } => Some((x * scale.0, y * scale.1)), _ => None };
+    let Some((anchor_x, anchor_y)) = events[..first].iter().rev().find_map(position) else { return Some(first); };
+    let is_moved = events[first + 1..].iter().filter_map(position).any(|(x, y)| (x - anchor_x).hypot(y - anchor_y) > DOUBLE_CLICK_DISTANCE);
+
+    if is_moved { None } else { Some(first) }
+}
+
+/// 把事件寫入即時記錄檔 (寫入失敗時停止寫入，不影響錄製)
+/// # 參數
+/// - `event` - &StoredEvent
+fn append_to_stream(event: &StoredEvent) {
+
+    let mut stream = lock_or_recover(&STREAM_LOG);
+    let Some(log) = stream.as_mut() else { return; };
+
+    if let Err(error) = log.append(event) {
+        println!("[Error] 寫入事件記錄檔失敗，停止寫入: {}", error);
+        *stream = None;
+    }
+}
+
+/// 清空即時記錄檔 (開始錄製時呼叫，避免下一次復原時混入上一次錄製的事件；清空失敗時停止寫入)
+fn truncate_stream() {
+
+    let mut stream = lock_or_recover(&STREAM_LOG);
+    let Some(log) = stream.as_mut() else { return; };
+
+    if let Err(error) = log.truncate() {
+        println!("[Error] 清空即時記錄檔失敗，停止寫入: {}", error);
+        *stream = None;
+    }
+}
+
+// MARK: 公開 API (預設錄製器)
+/// 開始錄製 (需先以 `listen_keyboard()` 啟動監聽執行緒)
+/// # 返回值
+/// - bool - 返回當前是否在錄製狀態
+pub fn record() -> bool { start_listen() }
+
+/// 停止錄製
+/// # 參數
+/// - `is_hotkey` - bool 是否通過熱鍵停止錄製 (會移除最後按下的熱鍵事件)
+/// # 返回值
+/// - bool - 返回當前是否仍在錄製狀態
+pub fn stop(is_hotkey: bool) -> bool { stop_listen(is_hotkey) }
+
+/// 取得目前錄製的所有事件
+/// # 返回值
+/// - Vec<StoredEvent>
+pub fn events() -> Vec<StoredEvent> { default_handle().events() }
+
+/// 取得一頁目前錄製的事件
+/// # 參數
+/// - `offset` - usize 第一個事件的位置
+/// - `limit` - usize 最多幾個事件
+/// # 返回值
+/// - EventPage
+pub fn events_page(offset: usize, limit: usize) -> EventPage { default_handle().events_page(offset, limit) }
+
+/// 是否正在錄製
+/// # 返回值
+/// - bool
+pub fn is_recording() -> bool { default_handle().is_recording() }
+
+/// 取得錄製時記錄的註記
+/// # 返回值
+/// - Vec<Annotation>
+pub fn annotations() -> Vec<Annotation> { default_handle().annotations() }
+
+/// 還原錄製內容會打出的文字 (不會打出字元的按鍵會略過)
+/// # 返回值
+/// - String
+pub fn extract_text() -> String { default_handle().extract_text() }
+
+/// 檢查預設錄製器的錄製內容是否可能包含敏感的輸入
+/// # 返回值
+/// - PrivacyReport
+pub fn privacy_scan() -> PrivacyReport { default_handle().privacy_scan() }
+
+/// 目前錄製內容的工作階段編號
+/// # 返回值
+/// - Option<String>
+pub fn current_session_id() -> Option<String> { default_handle().session_id() }
+
+/// 取得錄製內容的附加資訊
+/// # 返回值
+/// - RecordInfo
+pub fn record_info() -> RecordInfo { default_handle().record_info() }
+
+/// 設定錄製內容的顯示名稱與說明
+/// # 參數
+/// - `name` - String 名稱
+/// - `description` - String 說明
+pub fn set_record_info(name: String, description: String) { default_handle().set_record_info(name, description) }
+
+/// 設定每記錄一個事件時呼叫的 callback
+/// # 參數
+/// - `callback` - RecordCallback
+pub fn set_on_record(callback: RecordCallback) { default_handle().set_on_record(callback) }
+
+// MARK: 處理各種事件的具體行為
+/// 處理鍵盤事件 (更新按住的按鍵後判斷是否錄製)
+/// - 單獨按下再放開的修飾鍵 (例如只按一下 Shift) 與一般按鍵相同，按下與放開都會錄製
+/// # 參數
+/// - `event_type` - EventType 鍵盤事件
+/// # 返回值
+/// - bool - 是否要錄製 (非鍵盤事件返回 true)
+fn is_key_recorded(event_type: EventType) -> bool {
+
+    match event_type {
+        KeyPress(key) => { update_held_keys(&HELD_KEYS, key, true); _key_press_action(key) }
+        KeyRelease(key) => { update_held_keys(&HELD_KEYS, key, false); _key_release_action(key) }
+        _ => true,
+    }
+}
+
+/// 處理鍵盤按下事件 (不錄製的按鍵記下來，放開時也不錄製)
+/// # 參數
+/// - `key` - Key
+/// # 返回值
+/// - bool - 是否要錄製
+fn _key_press_action(key: Key) -> bool {
+
+    let is_abort = key == Key::Escape && ESCAPE_ABORTS.load(Ordering::SeqCst) && is_recording();
+    let is_skipped = is_abort || is_blocked(key) || (!CAPTURE_MEDIA_KEYS.load(Ordering::SeqCst) && media_key_name(key).is_some());
+
+    if is_skipped {
+        update_held_keys(&SKIPPED_PRESSES, key, true);
+        if is_abort { abort_recording(); }
+        return false;
+    }
+
+    update_held_keys(&SKIPPED_PRESSES, key, false);
+    true
+}
+
+/// 按下的按鍵是否符合不錄製的組合 (依目前按住的修飾鍵判斷，在記錄之前檢查)
+/// # 參數
+/// - `key` - Key
+/// # 返回值
+/// - bool
+fn is_blocked(key: Key) -> bool {
+
+    let blocklist = lock_or_recover(&KEY_BLOCKLIST);
+    if blocklist.is_empty() { return false; }
+
+    let held_modifiers: Vec<Modifier> = lock_or_recover(&HELD_KEYS).iter().filter_map(|held_key| key_modifier(*held_key)).collect();
+    blocklist.iter().any(|combo| combo.key == key && combo.modifiers.iter().all(|modifier| held_modifiers.contains(modifier)))
+}
+
+/// 取得按鍵對應的修飾鍵
+/// # 參數
+/// - `key` - Key
+/// # 返回值
+/// - Option<Modifier> - 不是修飾鍵時返回 None
+fn key_modifier(key: Key) -> Option<Modifier> {
+
+    match key {
+        Key::ControlLeft | Key::ControlRight => Some(Modifier::Control),
+        Key::ShiftLeft | Key::ShiftRight => Some(Modifier::Shift),
+        Key::Alt | Key::AltGr => Some(Modifier::Alt),
+        Key::MetaLeft | Key::MetaRight => Some(Modifier::Meta),
+        _ => None,
+    }
+}
+
+/// 取得修飾鍵對應的按鍵 (送出時使用左邊的按鍵)
+/// # 參數
+/// - `modifier` - Modifier
+/// # 返回值
+/// - Key
+fn modifier_key(modifier: Modifier) -> Key {
+
+    match modifier {
+        Modifier::Control => Key::ControlLeft,
+        Modifier::Shift => Key::ShiftLeft,
+        Modifier::Alt => Key::Alt,
+        Modifier::Meta => Key::MetaLeft,
+    }
+}
+
+/// 事件距離錄製開始的毫秒數
+/// # 參數
+/// - `event` - &StoredEvent
+/// - `origin` - Option<SystemTime> 錄製開始時間
+/// # 返回值
+/// - u64
+fn offset_ms_of(event: &StoredEvent, origin: Option<SystemTime>) -> u64 {
+    let origin = origin.unwrap_or(event.time);
+    event.elapsed.unwrap_or_else(|| event.time.duration_since(origin).unwrap_or_default()).as_millis() as u64
+}
+
+/// 以空白、Enter、Tab 與滑鼠點擊切開輸入的文字，找出疑似密碼的字串 (長度夠長且混合多種字元)
+/// # 參數
+/// - `events` - &[StoredEvent]
+/// - `is_caps_lock` - bool 錄製開始時的 Caps Lock 狀態
+/// - `origin` - Option<SystemTime> 錄製開始時間
+/// - `settings` - &PrivacyScanSettings
+/// # 返回值
+/// - Vec<PrivacyWarning>
+fn suspicious_text_warnings(events: &[StoredEvent], is_caps_lock: bool, origin: Option<SystemTime>, settings: &PrivacyScanSettings) -> Vec<PrivacyWarning> {
+
+    let classes: [fn(char) -> bool; 4] = [char::is_lowercase, char::is_uppercase, char::is_numeric, |character| !character.is_alphanumeric()];
+    let mut warnings = Vec::new();
+    let mut is_caps_lock = is_caps_lock;
+    let mut start = 0;
+
+    for (index, event) in events.iter().enumerate() {
+
+        let is_boundary = event.enabled && matches!(event.event_type, KeyPress(Key::Space | Key::Return | Key::KpReturn | Key::Tab) | ButtonPress(_));
+        if !is_boundary && index + 1 < events.len() { continue; }
+
+        let segment = &events[start..if is_boundary { index } else { index + 1 }];
+        let word = typed_text(segment, is_caps_lock);
+        let length = word.chars().count();
+        let class_count = classes.iter().filter(|class| word.chars().any(|character| class(character))).count();
+        let presses: Vec<&StoredEvent> = segment.iter().filter(|event| event.enabled && matches!(event.event_type, KeyPress(_))).collect();
+
+        if let (Some(first), Some(last)) = (presses.first(), presses.last()) {
+            if length >= settings.min_length && class_count >= settings.min_char_classes {
+                let message = format!("疑似密碼的字串 (長度 {}，混合 {} 種字元)", length, class_count);
+                warnings.push(PrivacyWarning { kind: "suspicious-text".to_string(), message, start_ms: offset_ms_of(first, origin), end_ms: offset_ms_of(last, origin) });
+            }
+        }
+
+        let caps_count = segment.iter().filter(|event| event.enabled && matches!(event.event_type, KeyPress(Key::CapsLock))).count();
+        if caps_count % 2 == 1 { is_caps_lock = !is_caps_lock; }
+        start = index + 1;
+    }
+
+    warnings
+}
+
+/// 找出在敏感的前景應用程式 (密碼管理工具、系統的密碼對話框…) 中的輸入 (只能判斷應用程式，無法得知輸入欄位)
+/// # 參數
+/// - `events` - &[StoredEvent]
+/// - `annotations` - &[Annotation] 錄製時記錄的前景應用程式變化
+/// - `origin` - Option<SystemTime> 錄製開始時間
+/// - `settings` - &PrivacyScanSettings
+/// # 返回值
+/// - Vec<PrivacyWarning>
+fn sensitive_app_warnings(events: &[StoredEvent], annotations: &[Annotation], origin: Option<SystemTime>, settings: &PrivacyScanSettings) -> Vec<PrivacyWarning> {
+
+    let mut warnings = Vec::new();
+
+    for (position, annotation) in annotations.iter().enumerate() {
+
+        let text = annotation.text.to_lowercase();
+        if !settings.sensitive_apps.iter().any(|app| !app.is_empty() && text.contains(&app.to_lowercase())) { continue; }
+
+        let end = annotations.get(position + 1).map_or(events.len(), |next| next.index).min(events.len());
+        let presses: Vec<&StoredEvent> = events[annotation.index.min(end)..end].iter().filter(|event| event.enabled && matches!(event.event_type, KeyPress(_))).collect();
+        let (Some(first), Some(last)) = (presses.first(), presses.last()) else { continue; };
+
+        let message = format!("在「{}」中輸入了 {} 個按鍵", annotation.text, presses.len());
+        warnings.push(PrivacyWarning { kind: "sensitive-app".to_string(), message, start_ms: offset_ms_of(first, origin), end_ms: offset_ms_of(last, origin) });
+    }
+
+    warnings
+}
+
+/// 還原鍵盤事件會打出的文字 (US QWERTY；Backspace 刪除前一個字元，Enter 為換行)
+/// - 方向鍵、功能鍵等不會打出字元的按鍵會略過，按住 Ctrl / Alt / Meta 時的按鍵視為快捷鍵，也會略過
+/// - 沒有放開事件的錄製內容，Shift 只套用到下一個打出字元的按鍵
+/// # 參數
+/// - `events` - &[StoredEvent]
+/// - `is_caps_lock` - bool 開始時 Caps Lock 是否開啟
+/// # 返回值
+/// - String
+fn typed_text(events: &[StoredEvent], is_caps_lock: bool) -> String {
+
+    let has_releases = events.iter().any(|event| matches!(event.event_type, KeyRelease(_)));
+    let mut text = String::new();
+    let mut is_caps_lock = is_caps_lock;
+    let mut modifiers: Vec<Modifier> = Vec::new();
+
+    for event in events.iter().filter(|event| event.enabled) {
+
+        match event.event_type {
+            KeyPress(Key::CapsLock) => { is_caps_lock = !is_caps_lock; }
+            KeyPress(Key::Backspace) => { text.pop(); }
+            KeyPress(key) => {
+                if let Some(modifier) = key_modifier(key) { if !modifiers.contains(&modifier) { modifiers.push(modifier); } continue; }
+                if modifiers.iter().any(|modifier| *modifier != Modifier::Shift) { continue; }
+
+                let is_shift = modifiers.contains(&Modifier::Shift);
+                let Some(character) = key_char(key, is_shift) else { continue; };
+                // Caps Lock 只影響英文字母，與 Shift 同時作用時還原成小寫
+                let character = match (is_caps_lock && character.is_ascii_alphabetic(), is_shift) {
+                    (true, true) => character.to_ascii_lowercase(),
+                    (true, false) => character.to_ascii_uppercase(),
+                    _ => character,
+                };
+
+                text.push(character);
+                if !has_releases { modifiers.clear(); }
+            }
+            KeyRelease(key) => {
+                if let Some(modifier) = key_modifier(key) { modifiers.retain(|held| *held != modifier); }
+            }
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// 解析按鍵組合 (修飾鍵以 `+` 連接，例如 `META+KeyQ`、`CTRL+SHIFT+KeyS`、`KeyQ`)
+/// # 參數
+/// - `combo` - &str
+/// # 返回值
+/// - Result<KeyCombo, String>
+fn parse_combo(combo: &str) -> Result<KeyCombo, String> {
+
+    let mut parts: Vec<&str> = combo.split('+').map(|part| part.trim()).collect();
+    let key = parse_key(parts.pop().unwrap_or_default())?;
+
+    let modifiers = parts.iter().map(|part| {
+        match part.to_ascii_uppercase().as_str() {
+            "CTRL" | "CONTROL" => Ok(Modifier::Control),
+            "SHIFT" => Ok(Modifier::Shift),
+            "ALT" | "OPTION" => Ok(Modifier::Alt),
+            "META" | "CMD" | "COMMAND" | "SUPER" | "WIN" => Ok(Modifier::Meta),
+            _ => Err(format!("無法辨識的修飾鍵: {}", part)),
+        }
+    }).collect::<Result<Vec<Modifier>, String>>()?;
+
+    Ok(KeyCombo { name: combo.trim().to_string(), modifiers, key })
+}
+
+/// 放棄錄製 (停止錄製並清除錄製內容；只發送 RecordAborted，不發送停止錄製的事件與通知，也不停止進行中的回放)
+fn abort_recording() {
+
+    let handle = default_handle();
+
+    handle.inner.is_listening.store(false, Ordering::SeqCst);
+    handle.lock().events.clear();
+    emit_event("RecordAborted", session_event_of(&handle.inner, None));
+}
+
+/// 處理鍵盤放開事件 (錄製；按下時沒有錄製的按鍵，放開時也不錄製)
+/// - 按下與放開依發生的順序記錄，回放時照同樣的順序送出，快速打字或遊戲中重疊的按鍵 (rollover) 不會被整理成一按一放
+/// # 參數
+/// - `key` - Key
+/// # 返回值
+/// - bool - 是否要錄製
+fn _key_release_action(key: Key) -> bool {
+
+    let is_skipped = lock_or_recover(&SKIPPED_PRESSES).contains(&key);
+    if is_skipped { update_held_keys(&SKIPPED_PRESSES, key, false); return false; }
+
+    true
+}
+
+/// 處理滑鼠移動事件
+/// # 參數
+/// - `event` - Event
+/// - `x` - f64
+/// - `y` - f64
+fn _mouse_move_action(event: &Event, _x: f64, _y: f64) {
+    record_event(event);
+}
+
+/// 處理滑鼠按鍵事件 (按下時會檢查是否為雙擊)
+/// # 參數
+/// - `event` - Event
+fn _button_action(event: &Event) {
+    record_event(event);
+}
+
+/// 處理滾輪 / 觸控板捲動事件
+/// - rdev 只提供 Wheel 事件：觸控板的雙指捲動 (macOS / Windows / Linux) 都會以 Wheel 記錄
+/// - macOS 的精確捲動 (像素) 會被轉成整數的 delta，回放時為逐行捲動，速度與慣性不會完全相同
+/// - 縮放、旋轉、三指滑動等手勢沒有對應的事件，無法錄製與回放
+/// # 參數
+/// - `event` - Event
+/// - `delta_x` - i64
+/// - `delta_y` - i64
+fn _wheel_action(event: &Event, _delta_x: i64, _delta_y: i64) {
+    record_event(event);
+}
+
+/// 送出模擬事件 (測試模式下只記錄將要送出的事件，不會呼叫 simulate)
+/// # 參數
+/// - `event_type` - &EventType
+/// # 返回值
+/// - Result<(), SimulateError>
+fn simulate_event(event_type: &EventType) -> Result<(), SimulateError> {
+
+    if TEST_MODE.load(Ordering::SeqCst) {
+        lock_or_recover(&CAPTURED_OUTPUT).push(format!("{:?}", event_type));
+        return Ok(());
+    }
+
+    mark_synthetic(event_type);
+
+    #[cfg(all(target_os = "linux", feature = "linux-uinput"))]
+    if let Some(result) = crate::uinput::emit(event_type) {
+        return result.map_err(|error| { println!("[Error] uinput 送出事件失敗: {}", error); SimulateError });
+    }
+
+    simulate(event_type)
+}
+
+/// 偵測這個平台可以模擬的事件種類
+/// - macOS 與 Windows：rdev 支援所有事件種類
+/// - Linux：rdev 透過 X11 (XTest) 模擬，沒有 DISPLAY 的工作階段 (例如純 Wayland) 全部無法模擬，可改用 `linux-uinput` feature
+/// # 返回值
+/// - SimulateCapabilities
+fn detect_simulate_capabilities() -> SimulateCapabilities {
+
+    let is_supported = !cfg!(target_os = "linux") || std::env::var_os("DISPLAY").is_some();
+    SimulateCapabilities { keyboard: is_supported, mouse_move: is_supported, mouse_button: is_supported, wheel: is_supported }
+}
+
+/// 取得目前可以模擬的事件種類 (測試模式時全部可以送出；使用 uinput 時滑鼠移動需要取得螢幕大小，沒有對應 evdev 按鍵的按鍵改由 rdev 送出)
+/// # 返回值
+/// - SimulateCapabilities
+pub fn simulate_capabilities() -> SimulateCapabilities {
+
+    if TEST_MODE.load(Ordering::SeqCst) { return SimulateCapabilities { keyboard: true, mouse_move: true, mouse_button: true, wheel: true }; }
+
+    #[cfg(all(target_os = "linux", feature = "linux-uinput"))]
+    if crate::uinput::is_enabled() {
+        let mouse_move = crate::uinput::supports_mouse_move() || SIMULATE_CAPABILITIES.mouse_move;
+        return SimulateCapabilities { keyboard: true, mouse_move, mouse_button: true, wheel: true };
+    }
+
+    *SIMULATE_CAPABILITIES
+}
+
+/// 送出模擬事件，失敗時依設定的次數重試 (每次重試的等待時間遞增)
+/// # 參數
+/// - `event_type` - &EventType
+/// # 返回值
+/// - Result<(), SimulateError> - 重試用完仍失敗時返回最後一次的錯誤
+fn simulate_with_retry(event_type: &EventType) -> Result<(), SimulateError> {
+
+    let retries = SIMULATE_RETRIES.load(Ordering::SeqCst);
+    let mut attempt = 0;
+
+    loop {
+        match simulate_event(event_type) {
+            Ok(()) => return Ok(()),
+            Err(error) if attempt >= retries => return Err(error),
+            Err(_) => {
+                attempt += 1;
+                SIMULATE_RETRY_COUNT.fetch_add(1, Ordering::SeqCst);
+                sleep(Duration::from_millis(5 * attempt as u64));
+            }
+        }
+    }
+}
+
+/// 模擬鍵盤按下事件
+/// - 送出錄製到的按鍵本身，左右兩邊的修飾鍵 (ShiftLeft / ShiftRight、ControlLeft / ControlRight、MetaLeft / MetaRight、Alt / AltGr) 不會被合併
+/// - AltGr 是右邊的 Alt：在有 AltGr 的配置上 Windows 會額外產生 ControlLeft，錄製時會一起記錄，回放時照原本的順序送出
+/// # 參數
+/// - `key` - Key
+fn _play_key_press(key: Key) {
+
+    match simulate_with_retry(&EventType::KeyPress(key)) {
+        Ok(_) => println!("成功模擬按鍵: {:?}", key),
+        Err(error) => println!("模擬鍵盤事件失敗: {:?}", error),
+    }
+}
+
+/// 模擬鍵盤放開事件
+/// # 參數
+/// - `key` - Key
+fn _play_key_release(key: Key) {
+
+    match simulate_with_retry(&EventType::KeyRelease(key)) {
+        Ok(_) => println!("成功模擬放開按鍵: {:?}", key),
+        Err(error) => println!("模擬鍵盤事件失敗: {:?}", error),
+    }
+}
+
+/// 把鎖定鍵切換成錄製時的狀態 (任一方無法取得狀態時不處理)
+/// # 參數
+/// - `recorded` - Option<LockState> 錄製時的狀態
+/// # 返回值
+/// - Option<LockState> - 有切換時返回使用者原本的狀態 (回放結束後還原用)
+fn sync_lock_state(recorded: Option<LockState>) -> Option<LockState> {
+
+    let (recorded, current) = (recorded?, lock_state()?);
+    if recorded == current { return None; }
+
+    toggle_locks(current, recorded);
+    Some(current)
+}
+
+/// 回放結束後把鎖定鍵還原成使用者原本的狀態
+/// # 參數
+/// - `original` - Option<LockState> 回放前的狀態
+fn restore_lock_state(original: Option<LockState>) {
+    if let (Some(original), Some(current)) = (original, lock_state()) { toggle_locks(current, original); }
+}
+
+/// 按下再放開狀態不同的鎖定鍵
+/// # 參數
+/// - `from` - LockState 目前的狀態
+/// - `to` - LockState 目標狀態
+fn toggle_locks(from: LockState, to: LockState) {
+    if from.caps_lock != to.caps_lock { _play_key_press(Key::CapsLock); _play_key_release(Key::CapsLock); }
+    if from.num_lock != to.num_lock { _play_key_press(Key::NumLock); _play_key_release(Key::NumLock); }
+}
+
+/// 取得主螢幕的大小
+/// # 返回值
+/// - Option<(f64, f64)> - 無法取得時返回 None
+fn current_display_size() -> Option<(f64, f64)> {
+
+    match display_size() {
+        Ok((width, height)) if width > 0 && height > 0 => Some((width as f64, height as f64)),
+        Ok(_) => None,
+        Err(error) => { println!("[Error] 無法取得螢幕大小: {:?}", error); None }
+    }
+}
+
+/// 把目前錄製內容的滑鼠路徑畫在主螢幕截圖上並輸出成 GIF 動畫
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// - `width` - Option<u32> 輸出的寬度 (高度依比例計算，None 表示與截圖相同)
+/// # 返回值
+/// - Result<usize, String> - 輸出的畫格數量，沒有滑鼠移動事件時返回錯誤
+#[cfg(feature = "demo-gif")]
+pub fn export_demo_gif(path: &str, width: Option<u32>) -> Result<usize, String> {
+
+    let handle = default_handle();
+    let (events, _) = handle.recorded_events();
+    let events = to_pixels(events, handle.record_info().coordinate_mode)?;
+    let display_size = current_display_size().ok_or("無法取得螢幕大小")?;
+
+    crate::demo::export_demo_gif(&events, display_size, path, width)
+}
+
+/// 把以比例記錄的滑鼠座標換回目前主螢幕的像素座標
+/// # 參數
+/// - `events` - Vec<StoredEvent>
+/// - `mode` - CoordinateMode 錄製時的座標方式
+/// # 返回值
+/// - Result<Vec<StoredEvent>, String> - 無法取得螢幕大小時返回錯誤
+fn to_pixels(events: Vec<StoredEvent>, mode: CoordinateMode) -> Result<Vec<StoredEvent>, String> {
+
+    if mode == CoordinateMode::Pixels { return Ok(events); }
+
+    let (width, height) = current_display_size().ok_or("無法取得螢幕大小，不能換算滑鼠座標")?;
+
+    Ok(events.into_iter().map(|event| match event.event_type {
+        MouseMove { x, y } => StoredEvent { event_type: MouseMove { x: x * width, y: y * height }, ..event },
+        _ => event,
+    }).collect())
+}
+
+/// 取得回放時滑鼠可移動的範圍 (主螢幕內縮安全邊界)
+/// # 返回值
+/// - Option<(f64, f64, f64)> - (最小座標, 最大 x, 最大 y)，沒有設定邊界或無法取得螢幕大小時返回 None
+fn mouse_safe_area() -> Option<(f64, f64, f64)> {
+
+    let margin = MOUSE_SAFE_MARGIN.load(Ordering::SeqCst) as f64;
+    if margin <= 0.0 { return None; }
+
+    let (width, height) = current_display_size()?;
+    let (max_x, max_y) = ((width - 1.0 - margin).max(margin), (height - 1.0 - margin).max(margin));
+
+    Some((margin, max_x, max_y))
+}
+
+/// 模擬滑鼠移動事件
+/// # 參數
+/// - `x` - f64
+/// - `y` - f64
+fn _play_mouse_move(x: f64, y: f64) {
+
+    if let Err(error) = simulate_event(&EventType::MouseMove { x, y }) {
+        println!("模擬滑鼠事件失敗: {:?}", error);
+    }
+}
+
+/// 模擬滑鼠按鍵事件
+/// # 參數
+/// - `event_type` - EventType (ButtonPress / ButtonRelease)
+/// - `button` - Button
+fn _play_button(event_type: EventType, button: Button) {
+
+    if let Err(error) = simulate_event(&event_type) {
+        println!("模擬滑鼠按鍵 {:?} 失敗: {:?}", button, error);
+    }
+}
+
+/// 模擬滾輪事件
+/// # 參數
+/// - `delta_x` - i64
+/// - `delta_y` - i64
+fn _play_wheel(delta_x: i64, delta_y: i64) {
+
+    if let Err(error) = simulate_event(&EventType::Wheel { delta_x, delta_y }) {
+        println!("模擬滾輪事件失敗: {:?}", error);
+    }
+}
+
+/// 回調事件處理 (鍵盤 / 滑鼠)
+/// # 參數
+/// - `event` - Event
+fn callback(event: Event) {
+
+    match event.event_type {
+        KeyPress(_) | KeyRelease(_) => { if is_key_recorded(event.event_type) { record_event(&event); } }
+        MouseMove { x, y } => { _mouse_move_action(&event, x, y); }
+        ButtonPress(_) | ButtonRelease(_) => { _button_action(&event); }
+        Wheel { delta_x, delta_y } => { _wheel_action(&event, delta_x, delta_y); }
+    }
+}
+
+// MARK: 相關功能實現
+/// 開始監聽鍵盤和滑鼠事件 (新執行緒)
+/// # 返回值
+/// - JoinHandle<Result<(), ListenError>> - 執行緒的 handle，可用於檢查執行狀態
+pub fn listen_keyboard() -> JoinHandle<Result<(), ListenError>> {
+
+    spawn(move || {
+        listen_keyboard_action().map_err(|error| {
+            println!("[Error] 鍵盤監聽執行緒發生錯誤: {:?}", error);
+            *lock_or_recover(&LAST_LISTEN_ERROR) = Some(format!("{:?}", error));
+            error
+        })
+    })
+}
+
+/// 開始監聽鍵盤和滑鼠事件
+/// # 返回值
+/// - Result<(), ListenError>
+fn listen_keyboard_action() -> Result<(), ListenError> {
+
+    match listen(callback) {
+        Ok(()) => Ok(()),
+        Err(error) => { Err(error) },
+    }
+
+    // let result = listen(callback).map_err(|error| { error })
+}
+
+/// 在錄製期間監看前景應用程式，變化時寫入註記，設定只錄製特定應用程式時依焦點暫停 / 繼續錄製 (新執行緒，停止錄製或重新開始錄製後結束)
+/// # 參數
+/// - `inner` - Arc<RecorderInner> 要記錄的錄製器
+/// - `recording_id` - u64 這次錄製的編號
+fn spawn_focus_watcher(inner: Arc<RecorderInner>, recording_id: u64) {
+
+    spawn(move || {
+
+        let is_current = || inner.is_listening.load(Ordering::SeqCst) && inner.recording_id.load(Ordering::SeqCst) == recording_id;
+        let mut last_app: Option<String> = None;
+
+        while is_current() {
+
+            let app = frontmost_app();
+
+            if app.is_some() && app != last_app {
+
+                let name = app.as_deref().unwrap_or_default();
+
+                if RECORD_FOCUS_CHANGES.load(Ordering::SeqCst) {
+                    let text = format!("前景應用程式: {}", name);
+                    let mut recorder = lock_or_recover(&inner.recorder);
+                    let index = recorder.events.len();
+                    recorder.annotations.push(Annotation { index, time: inner.clock.now(), text });
+                    drop(recorder);
+                }
+
+                let is_paused = lock_or_recover(&RECORD_ONLY_APP).as_deref().is_some_and(|target| !is_app_match(name, target));
+                if inner.is_paused.swap(is_paused, Ordering::SeqCst) != is_paused {
+                    emit_event(if is_paused { "RecordPaused" } else { "RecordResumed" }, session_event_of(&inner, Some(name.to_string())));
+                }
+
+                last_app = app;
+            }
+
+            sleep(Duration::from_millis(500));
+        }
+    });
+}
+
+/// 建立錄製工作階段的事件內容
+/// # 參數
+/// - `inner` - &RecorderInner
+/// - `app` - Option<String> 前景應用程式
+/// # 返回值
+/// - RecordSessionEvent
+fn session_event_of(inner: &RecorderInner, app: Option<String>) -> RecordSessionEvent {
+    let recorder = lock_or_recover(&inner.recorder);
+    RecordSessionEvent { session_id: recorder.info.session_id.clone(), event_count: recorder.events.len(), app }
+}
+
+/// 產生新的工作階段編號 (UUID v4 格式，以標準函式庫的隨機雜湊種子產生)
+/// # 返回值
+/// - String
+fn new_session_id() -> String {
+
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let random = || RandomState::new().build_hasher().finish();
+    let (high, low) = (random(), random());
+    let high = (high & 0xffff_ffff_ffff_0fff) | 0x4000; // 版本 4
+    let low = (low & 0x3fff_ffff_ffff_ffff) | 0x8000_0000_0000_0000; // RFC 4122 變體
+
+    format!("{:08x}-{:04x}-{:04x}-{:04x}-{:012x}", high >> 32, (high >> 16) & 0xffff, high & 0xffff, low >> 48, low & 0xffff_ffff_ffff)
+}
+
+/// 前景應用程式是否為指定的應用程式 (不分大小寫；Linux 取得的是視窗標題，以包含比對)
+/// # 參數
+/// - `app` - &str 前景應用程式名稱
+/// - `target` - &str 指定的應用程式
+/// # 返回值
+/// - bool
+fn is_app_match(app: &str, target: &str) -> bool {
+    app.to_lowercase().contains(&target.to_lowercase())
+}
+
+/// 將事件分派給所有錄製器記錄 (依各自的擷取種類決定是否記錄；自己送出的模擬事件不記錄)
+/// # 參數
+/// - `event` - Event
+fn record_event(event: &Event) {
+    if take_synthetic(&event.event_type) { return; }
+    for inner in live_handles().iter() { record_into(inner, event); }
+}
+
+/// 記錄送出的模擬事件
+/// # 參數
+/// - `event_type` - &EventType
+fn mark_synthetic(event_type: &EventType) {
+
+    let mut events = lock_or_recover(&SYNTHETIC_EVENTS);
+    if events.len() >= 256 { events.pop_front(); }
+    events.push_back((Instant::now(), *event_type));
+}
+
+/// 取消標示還沒被監聽到的模擬事件 (從最新的記錄找起，例如文字輸入失敗、改回逐鍵模擬時)
+/// # 參數
+/// - `event_types` - &[EventType] 之前標示的事件
+fn unmark_synthetic(event_types: &[EventType]) {
+
+    let mut events = lock_or_recover(&SYNTHETIC_EVENTS);
+
+    for event_type in event_types.iter().rev() {
+        if let Some(index) = events.iter().rposition(|(_, synthetic)| synthetic == event_type) { events.remove(index); }
+    }
+}
+
+/// 監聽到的事件是否為剛才自己送出的模擬事件 (符合時移除該筆記錄)
+/// - rdev 的事件沒有標示來源 (實體裝置或模擬)，各平台都以最近送出的事件比對；滑鼠移動允許 1 像素的誤差
+/// - 模擬事件依送出的順序被監聽到，只比對最早的一筆，後面還沒輪到的記錄不會吃掉實體輸入
+/// - 限制：比對時間內與最早一筆相同的實體輸入 (例如回放送出 A 的同時使用者也按了 A) 仍無法分辨，會視為模擬事件不錄製
+/// # 參數
+/// - `event_type` - &EventType 監聽到的事件
+/// # 返回值
+/// - bool
+fn take_synthetic(event_type: &EventType) -> bool {
+
+    let mut events = lock_or_recover(&SYNTHETIC_EVENTS);
+    events.retain(|(time, _)| time.elapsed() <= SYNTHETIC_MATCH_WINDOW);
+
+    let is_match = events.front().is_some_and(|(_, synthetic)| match (synthetic, event_type) {
+        (MouseMove { x, y }, MouseMove { x: event_x, y: event_y }) => (x - event_x).abs() <= 1.0 && (y - event_y).abs() <= 1.0,
+        _ => synthetic == event_type,
+    });
+
+    if is_match { events.pop_front(); }
+    is_match
+}
+
+/// 取得仍在使用中的錄製器 (同時移除已釋放的錄製器)
+/// # 返回值
+/// - Vec<Arc<RecorderInner>>
+fn live_handles() -> Vec<Arc<RecorderInner>> {
+    let mut handles = lock_or_recover(&HANDLES);
+    handles.retain(|handle| handle.strong_count() > 0);
+    handles.iter().filter_map(|handle| handle.upgrade()).collect()
+}
+
+// MARK: 檔案存取
+/// 儲存目前的錄製內容
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<(), String>
+pub fn save_to_file(path: &str) -> Result<(), String> {
+    default_handle().save_to(&FileStorage::new(path))
+}
+
+/// 將一段時間範圍內的錄製內容存成獨立的錄製檔案
+/// # 參數
+/// - `start_ms` - u64 範圍開始 (距離錄製開始的毫秒數)
+/// - `end_ms` - u64 範圍結束
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<usize, String> - 儲存的事件數量
+pub fn export_time_slice(start_ms: u64, end_ms: u64, path: &str) -> Result<usize, String> {
+    default_handle().save_time_slice_to(start_ms, end_ms, &FileStorage::new(path))
+}
+
+/// 載入錄製檔案，取代目前的錄製內容
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<usize, String> - 載入的事件數量
+pub fn load_from_file(path: &str) -> Result<usize, String> {
+    default_handle().load_from(&FileStorage::new(path))
+}
+
+/// 設定錄製時把每個事件即時寫入記錄檔 (每行一個 JSON 事件，程式當掉時可用 `recover_from_log` 載入)
+/// # 參數
+/// - `path` - Option<&str> 檔案路徑 (None 表示停止寫入；已存在的檔案會先保留，開始錄製時才清空，只留下這次錄製的事件)
+/// # 返回值
+/// - Result<(), String> - 無法開啟檔案時返回錯誤
+pub fn set_stream_to_file(path: Option<&str>) -> Result<(), String> {
+    let log = path.map(EventLog::open).transpose()?;
+    *lock_or_recover(&STREAM_LOG) = log;
+    Ok(())
+}
+
+/// 載入即時記錄檔，取代目前的錄製內容
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<usize, String> - 載入的事件數量
+pub fn recover_from_log(path: &str) -> Result<usize, String> {
+    default_handle().load_from(&LogStorage::new(path))
+}
+
+// MARK: 編輯
+/// 將錄製的按鍵換成另一種鍵盤配置 (支援 QWERTY / Dvorak / Colemak)
+/// # 參數
+/// - `from` - &str 錄製時的配置名稱
+/// - `to` - &str 回放時的配置名稱
+/// # 返回值
+/// - Result<(), String> - 未知的配置名稱時返回錯誤
+pub fn remap_layout(from: &str, to: &str) -> Result<(), String> { default_handle().remap_layout(from, to) }
+
+/// 以按鍵名稱清單建立錄製內容，取代目前的錄製內容
+/// # 參數
+/// - `keys` - &[String] 按鍵名稱
+/// - `gap_ms` - u64 每個事件之間的間隔（毫秒）
+/// # 返回值
+/// - Result<usize, String> - 建立的事件數量
+pub fn build_from_keys(keys: &[String], gap_ms: u64) -> Result<usize, String> { default_handle().build_from_keys(keys, gap_ms) }
+
+/// 將錄製內容從指定位置分成兩段，移除並返回後半段
+/// # 參數
+/// - `index` - usize 後半段的第一個事件位置
+/// # 返回值
+/// - Result<Vec<StoredEvent>, String> - 後半段的事件 (時間從 0 開始)
+pub fn split_record(index: usize) -> Result<Vec<StoredEvent>, String> { default_handle().split(index) }
+
+/// 啟用 / 停用單一事件
+/// # 參數
+/// - `index` - usize 事件位置
+/// - `enabled` - bool 是否啟用
+/// # 返回值
+/// - Result<(), String> - 位置超出範圍時返回錯誤
+pub fn set_event_enabled(index: usize, enabled: bool) -> Result<(), String> { default_handle().set_event_enabled(index, enabled) }
+
+/// 移除最後一個鍵盤事件之後的所有事件
+/// # 返回值
+/// - usize - 移除的事件數量
+pub fn trim_after_last_key() -> usize { default_handle().trim_after_last_key() }
+
+/// 遮蔽預設錄製器時間範圍內的按鍵
+/// # 參數
+/// - `start_ms` - u64 開始時間 (距離錄製開始的毫秒數)
+/// - `end_ms` - u64 結束時間 (距離錄製開始的毫秒數，包含)
+/// # 返回值
+/// - Result<usize, String> - 遮蔽的事件數量
+pub fn redact_range(start_ms: u64, end_ms: u64) -> Result<usize, String> { default_handle().redact_range(start_ms, end_ms) }
+
+/// 把預設錄製器中使用指定按鍵的鍵盤事件改成另一個按鍵
+/// # 參數
+/// - `from` - &str 原本的按鍵名稱 (例如 `KeyA`)
+/// - `to` - &str 新的按鍵名稱
+/// # 返回值
+/// - Result<usize, String> - 修改的事件數量，無法辨識按鍵名稱時返回錯誤
+pub fn replace_key(from: &str, to: &str) -> Result<usize, String> {
+    Ok(default_handle().replace_key(parse_key(from)?, parse_key(to)?))
+}
+
+/// 把預設錄製器中位於來源螢幕上的滑鼠座標換算到目標螢幕
+/// # 參數
+/// - `from` - DisplayBounds 來源螢幕
+/// - `to` - DisplayBounds 目標螢幕
+/// # 返回值
+/// - Result<usize, String> - 換算的事件數量
+pub fn remap_display(from: DisplayBounds, to: DisplayBounds) -> Result<usize, String> { default_handle().remap_display(from, to) }
+
+/// 把預設錄製器的錄製內容輸出成 CSV (offset_ns,event_type,key,button,x,y)
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<usize, String> - 輸出的事件數量
+pub fn export_csv(path: &str) -> Result<usize, String> { default_handle().export_csv(path) }
+
+/// 以鍵盤與滑鼠兩個軌道取得預設錄製器的錄製內容
+/// # 返回值
+/// - Tracks
+pub fn tracks() -> Tracks { default_handle().tracks() }
+
+/// 設定預設錄製器回放時是否略過某個軌道的事件
+/// # 參數
+/// - `track` - &str "keyboard" 或 "mouse"
+/// - `muted` - bool
+/// # 返回值
+/// - Result<TrackMute, String> - 未知的軌道時返回錯誤
+pub fn set_track_muted(track: &str, muted: bool) -> Result<TrackMute, String> { default_handle().set_track_muted(track, muted) }
+
+/// 依時間重新排序目前錄製內容的事件並修正倒退的時間
+/// # 返回值
+/// - usize - 重新排序或調整時間的事件數量
+pub fn repair_timing() -> usize { default_handle().repair_timing() }
+
+/// 把每個事件的間隔四捨五入成指定毫秒數的倍數
+/// # 參數
+/// - `grid_ms` - u64 間隔的單位 (毫秒)
+/// # 返回值
+/// - Result<usize, String> - 間隔有改變的事件數量
+pub fn quantize_timing(grid_ms: u64) -> Result<usize, String> { default_handle().quantize_timing(grid_ms) }
+
+/// 依比例調整事件之間的間隔，讓錄製內容的總長度變成指定的毫秒數
+/// # 參數
+/// - `target_ms` - u64 目標總長度 (毫秒)
+/// # 返回值
+/// - Result<u64, String> - 原本的總長度 (毫秒)
+pub fn fit_duration(target_ms: u64) -> Result<u64, String> { default_handle().fit_duration(target_ms) }
+
+/// 設定單一事件的註解
+/// # 參數
+/// - `index` - usize 事件位置
+/// - `label` - String 註解 (空字串表示清除)
+/// # 返回值
+/// - Result<(), String> - 位置超出範圍時返回錯誤
+pub fn set_event_label(index: usize, label: String) -> Result<(), String> { default_handle().set_event_label(index, label) }
+
+/// 復原上一次的編輯
+/// # 返回值
+/// - bool - 有可復原的編輯時返回 true
+pub fn undo() -> bool { default_handle().undo() }
+
+/// 重做上一次被復原的編輯
+/// # 返回值
+/// - bool - 有可重做的編輯時返回 true
+pub fn redo() -> bool { default_handle().redo() }
+
+// MARK: 暫存的錄製內容
+/// 將目前的錄製內容以指定名稱暫存
+/// # 參數
+/// - `name` - &str 名稱
+/// # 返回值
+/// - usize - 暫存的事件數量
+pub fn save_slot(name: &str) -> usize { default_handle().store_slot(name) }
+
+/// 把目前的錄製內容複製成快照暫存起來 (不寫入檔案)
+/// # 參數
+/// - `name` - &str 名稱
+/// - `overwrite` - bool 名稱已存在時是否覆蓋
+/// # 返回值
+/// - Result<usize, String> - 暫存的事件數量
+pub fn snapshot_to_slot(name: &str, overwrite: bool) -> Result<usize, String> { default_handle().snapshot_to_slot(name, overwrite) }
+
+/// 載入暫存的錄製內容，取代目前的錄製內容
+/// # 參數
+/// - `name` - &str 名稱
+/// # 返回值
+/// - Result<usize, String> - 載入的事件數量
+pub fn load_slot(name: &str) -> Result<usize, String> { default_handle().restore_slot(name) }
+
+/// 設定暫存錄製內容的標籤 (會隨錄製檔案一起儲存)
+/// # 參數
+/// - `name` - &str 名稱
+/// - `tags` - Vec<String> 標籤
+/// # 返回值
+/// - Result<(), String> - 找不到該名稱時返回錯誤
+pub fn set_slot_tags(name: &str, tags: Vec<String>) -> Result<(), String> {
+
+    let mut slots = lock_or_recover(&SLOTS);
+    let slot = slots.get_mut(name).ok_or_else(|| format!("找不到錄製內容: {}", name))?;
+    slot.info.tags = tags;
+
+    Ok(())
+}
+
+/// 在目前錄製內容的指定事件之前呼叫另一個暫存的錄製內容 (回放時展開)
+/// # 參數
+/// - `index` - usize 事件位置 (等於事件數量時表示放在最後)
+/// - `name` - &str 暫存的名稱
+/// # 返回值
+/// - Result<(), String> - 位置超出範圍或找不到該名稱時返回錯誤
+pub fn add_slot_call(index: usize, name: &str) -> Result<(), String> { default_handle().add_slot_call(index, name) }
+
+/// 移除目前錄製內容指定位置的暫存錄製內容呼叫
+/// # 參數
+/// - `index` - usize 事件位置
+/// # 返回值
+/// - usize - 移除的數量
+pub fn remove_slot_calls(index: usize) -> usize { default_handle().remove_slot_calls(index) }
+
+/// 在目前錄製內容的指定事件之前加入依按鍵狀態選擇的分支
+/// # 參數
+/// - `index` - usize 事件位置 (等於事件數量時表示放在最後)
+/// - `key` - &str 按鍵名稱
+/// - `then_slot` - Option<String> 按住時回放的暫存名稱
+/// - `else_slot` - Option<String> 沒有按住時回放的暫存名稱
+/// # 返回值
+/// - Result<(), String> - 未知的按鍵、位置超出範圍或找不到暫存名稱時返回錯誤
+pub fn add_branch(index: usize, key: &str, then_slot: Option<String>, else_slot: Option<String>) -> Result<(), String> {
+    default_handle().add_branch(index, parse_key(key)?, then_slot, else_slot)
+}
+
+/// 移除目前錄製內容指定位置的分支
+/// # 參數
+/// - `index` - usize 事件位置
+/// # 返回值
+/// - usize - 移除的數量
+pub fn remove_branches(index: usize) -> usize { default_handle().remove_branches(index) }
+
+/// 以名稱或標籤搜尋暫存的錄製內容 (不分大小寫的部分比對)
+/// # 參數
+/// - `query` - &str 搜尋字串 (空字串時返回全部)
+/// # 返回值
+/// - Vec<String> - 符合的名稱 (依名稱排序)
+pub fn search_slots(query: &str) -> Vec<String> {
+
+    let query = query.to_lowercase();
+    let is_match = |text: &str| text.to_lowercase().contains(&query);
+
+    let mut names: Vec<String> = lock_or_recover(&SLOTS).iter()
+        .filter(|(name, slot)| is_match(name) || slot.info.tags.iter().any(|tag| is_match(tag)))
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    names.sort();
+    names
+}
+
+// MARK: 錄製
+/// 開始監聽錄製鍵盤事件
+/// # 返回值
+/// - bool - 返回當前是否在監聽狀態
+pub(crate) fn start_listen() -> bool { default_handle().start() }
+
+/// 停止監聽錄製鍵盤事件
+/// # 參數
+/// - `is_hotkey` - bool 是否通過熱鍵停止錄製
+/// # 返回值
+/// - bool - 返回當前是否仍在監聽狀態
+pub(crate) fn stop_listen(is_hotkey: bool) -> bool { default_handle().stop(is_hotkey) }
+
+// MARK: 回放
+/// 註冊一個新的回放
+/// # 參數
+/// - `events` - Vec<StoredEvent> 要回放的事件
+/// - `started_at` - Option<SystemTime> 錄製開始的時間
+/// - `stop` - Arc<AtomicBool> 這次回放的停止訊號 (stop_playback 與超過最長執行時間時設定)
+/// - `recorder_stop` - Arc<AtomicBool> 所屬錄製器的停止回放訊號
+/// - `clock` - Arc<dyn Clock> 回放使用的時間來源
+/// - `options` - PlaybackOptions 回放選項
+/// # 返回值
+/// - (u64, Arc<Playback>) - 回放編號與其控制資訊
+pub(crate) fn register_playback(events: Vec<StoredEvent>, started_at: Option<SystemTime>, stop: Arc<AtomicBool>, recorder_stop: Arc<AtomicBool>, clock: Arc<dyn Clock>, options: PlaybackOptions, branches: Vec<(usize, SlotBranch)>) -> (u64, Arc<Playback>) {
+
+    let id = NEXT_PLAYBACK_ID.fetch_add(1, Ordering::SeqCst);
+    let playback = Arc::new(Playback {
+        events,
+        started_at,
+        index: AtomicUsize::new(0),
+        stop,
+        recorder_stop,
+        held_keys: Mutex::new(Vec::new()),
+        clock,
+        options,
+        branches,
+    });
+
+    lock_or_recover(&PLAYBACKS).insert(id, playback.clone());
+    (id, playback)
+}
+
+/// 移除已結束的回放
+/// # 參數
+/// - `id` - u64 回放編號
+pub(crate) fn unregister_playback(id: u64) {
+
+    let mut playbacks = lock_or_recover(&PLAYBACKS);
+    playbacks.remove(&id);
+
+    // 所有回放都結束後取消暫停，避免下一次回放一開始就被暫停
+    if playbacks.is_empty() { PLAYBACK_PAUSED.store(false, Ordering::SeqCst); }
+}
+
+impl Playback {
+
+    /// 是否已收到停止訊號 (單一回放或所屬錄製器)
+    /// # 返回值
+    /// - bool
+    fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::SeqCst) || self.recorder_stop.load(Ordering::SeqCst)
+    }
+}
+
+/// 更新按住中的按鍵清單
+/// # 參數
+/// - `held_keys` - &Mutex<Vec<Key>>
+/// - `key` - Key
+/// - `is_pressed` - bool 是否為按下
+fn update_held_keys(held_keys: &Mutex<Vec<Key>>, key: Key, is_pressed: bool) {
+
+    let mut held_keys = lock_or_recover(held_keys);
+
+    if is_pressed {
+        if !held_keys.contains(&key) { held_keys.push(key); }
+        return;
+    }
+
+    held_keys.retain(|held_key| *held_key != key);
+}
+
+/// 記錄回放中按住 / 放開的按鍵 (同時更新全局的按住清單)
+/// # 參數
+/// - `playback` - &Playback
+/// - `key` - Key
+/// - `is_pressed` - bool 是否為按下
+fn track_held_key(playback: &Playback, key: Key, is_pressed: bool) {
+    update_held_keys(&playback.held_keys, key, is_pressed);
+    update_held_keys(&HELD_KEYS, key, is_pressed);
+}
+
+/// 該回放是否有按住中的修飾鍵
+/// # 參數
+/// - `playback` - &Playback
+/// # 返回值
+/// - bool
+fn has_held_modifier(playback: &Playback) -> bool {
+    lock_or_recover(&playback.held_keys).iter().any(|key| key_modifier(*key).is_some())
+}
+
+/// 以系統的文字輸入一次送出累積的文字 (失敗或平台不支援時改回逐鍵模擬)
+/// # 參數
+/// - `pending` - &mut Vec<(Key, String)> 累積的按鍵與錄製時產生的文字 (送出後清空)
+fn flush_pending_text(pending: &mut Vec<(Key, String)>) {
+
+    if pending.is_empty() { return; }
+
+    let text: String = pending.iter().map(|(_, text)| text.as_str()).collect();
+
+    // 文字輸入送出的按鍵也會被監聽到，先標示成自己送出的事件，避免回放時同時錄製到
+    let synthetic: Vec<EventType> = pending.iter().flat_map(|(key, _)| [KeyPress(*key), KeyRelease(*key)]).collect();
+    for event_type in synthetic.iter() { mark_synthetic(event_type); }
+
+    if let Err(error) = type_text(&text) {
+        unmark_synthetic(&synthetic);
+        println!("[Error] 文字輸入失敗，改為逐鍵模擬: {}", error);
+        for (key, _) in pending.iter() { _play_key_press(*key); _play_key_release(*key); }
+    }
+
+    pending.clear();
+}
+
+/// 放開該回放仍按住的所有按鍵 (避免中途停止時按鍵卡住)
+/// # 參數
+/// - `playback` - &Playback
+fn release_held_keys(playback: &Playback) {
+
+    let held_keys: Vec<Key> = lock_or_recover(&playback.held_keys).drain(..).collect();
+
+    for key in held_keys {
+        _play_key_release(key);
+        update_held_keys(&HELD_KEYS, key, false);
+    }
+}
+
+/// 取得目前認定為按住中的按鍵 (錄製時監聽到的按鍵與回放送出的按鍵)
+/// # 返回值
+/// - Vec<String> - 按鍵名稱
+pub fn held_key_names() -> Vec<String> {
+    lock_or_recover(&HELD_KEYS).iter().map(|key| format!("{:?}", key)).collect()
+}
+
+/// 放開所有認定為按住中的按鍵
+/// # 返回值
+/// - usize - 放開的按鍵數量
+pub fn release_all_held_keys() -> usize {
+
+    let held_keys: Vec<Key> = lock_or_recover(&HELD_KEYS).drain(..).collect();
+
+    for playback in lock_or_recover(&PLAYBACKS).values() { lock_or_recover(&playback.held_keys).clear(); }
+    for key in held_keys.iter() { _play_key_release(*key); }
+
+    held_keys.len()
+}
+
+/// 執行回放 (設定提高優先權時，回放期間提高目前執行緒的優先權，結束後還原)
+/// - 最長執行時間由開始回放的地方以 start_runtime_watchdog 計算，播放清單的多個檔案與分支共用同一段時間
+/// # 參數
+/// - `playback` - &Playback 該回放的控制資訊 (包含要回放的事件)
+/// # 返回值
+/// - bool - 如果回放過程中被停止，返回 true；否則返回 false
+pub(crate) fn run_playback(playback: &Playback) -> bool {
+
+    let original_priority = if PLAYBACK_HIGH_PRIORITY.load(Ordering::SeqCst) { raise_current_thread() } else { None };
+    let is_stop = run_playback_loop(playback);
+    if let Some(original_priority) = original_priority { restore_current_thread(original_priority); }
+
+    is_stop
+}
+
+/// 設定最長執行時間時，開始計算一次回放的執行時間，超過時只停止這次回放，並發送 "runtime-limit" 事件 (新執行緒，回放結束後隨即結束)
+/// - 其他同時進行的回放不受影響
+/// # 參數
+/// - `stop` - Arc<AtomicBool> 這次回放的停止訊號
+/// # 返回值
+/// - Arc<AtomicBool> - 回放結束時設為 true，讓計時的執行緒結束 (沒有設定最長執行時間時不會計時)
+fn start_runtime_watchdog(stop: Arc<AtomicBool>) -> Arc<AtomicBool> {
+
+    let is_finished = Arc::new(AtomicBool::new(false));
+    let max_runtime_ms = MAX_PLAYBACK_RUNTIME_MS.load(Ordering::SeqCst);
+    if max_runtime_ms == 0 { return is_finished; }
+
+    let is_watch_finished = is_finished.clone();
+
+    spawn(move || {
+        if wait_unless_stopped(Duration::from_millis(max_runtime_ms), &[&is_watch_finished, &stop]) { return; }
+
+        stop.store(true, Ordering::SeqCst);
+        emit_event("runtime-limit", max_runtime_ms);
+    });
+
+    is_finished
+}
+
+/// 執行回放的主要迴圈
+/// # 參數
+/// - `playback` - &Playback 該回放的控制資訊 (包含要回放的事件)
+/// # 返回值
+/// - bool - 如果回放過程中被停止，返回 true；否則返回 false
+fn run_playback_loop(playback: &Playback) -> bool {
+
+    let events = &playback.events;
+    if events.is_empty() { return false; }
+
+    let mut is_stop = false;
+    let delays = playback_delays(playback);
+
+    let move_threshold = f64::from_bits(PLAYBACK_MOVE_THRESHOLD_BITS.load(Ordering::SeqCst));
+    let mut last_position: Option<(f64, f64)> = None;
+    let is_cpu_friendly = PLAYBACK_CPU_FRIENDLY.load(Ordering::SeqCst);
+    let mut zero_gap_run = 0;
+    let mut sent_count = events.len();
+    let modifier_settle = Duration::from_millis(MODIFIER_SETTLE_MS.load(Ordering::SeqCst));
+    let min_modifier_hold = Duration::from_millis(MIN_MODIFIER_HOLD_MS.load(Ordering::SeqCst));
+    let mut last_modifier_press: Option<Duration> = None; // 回放時鐘的單調時間
+    let is_unicode_injection = UNICODE_INJECTION.load(Ordering::SeqCst) && !TEST_MODE.load(Ordering::SeqCst);
+    let mut pending_text: Vec<(Key, String)> = Vec::new();
+    let mut absorbed_keys: Vec<Key> = Vec::new();
+    let safe_area = mouse_safe_area();
+    let transform = *lock_or_recover(&MOUSE_TRANSFORM);
+    let capabilities = simulate_capabilities();
+    let interpolation_steps = MOUSE_INTERPOLATION_STEPS.load(Ordering::SeqCst);
+    let mut unsupported_count = 0;
+
+    for ((index, event), delay) in events.iter().enumerate().zip(delays) {
+
+        if playback.is_stopped() || !wait_while_paused(playback) { is_stop = true; sent_count = index; break; }
+
+        playback.index.store(index, Ordering::SeqCst);
+
+        // 分支在前一個事件送出後判斷 (先送出累積的文字)，回放完選擇的內容再繼續
+        if playback.branches.iter().any(|(position, _)| *position == index) {
+            flush_pending_text(&mut pending_text);
+            if run_branches_at(playback, index) { is_stop = true; sent_count = index; break; }
+        }
+
+        // 停用或不在回放種類內的事件不送出 (略過等待時間的設定已反映在 delay)
+        if !is_played(playback, event) {
+            wait_delay(playback, delay);
+            continue;
+        }
+
+        // 這個平台無法模擬的事件略過 (保留等待時間)，結束後一併警告
+        if !capabilities.supports(&event.event_type) {
+            unsupported_count += 1;
+            wait_delay(playback, delay);
+            continue;
+        }
+
+        // Unicode 輸入模式：沒有按住修飾鍵時，連續有錄製文字的按鍵先累積起來 (只等待第一個按鍵的時間)，遇到其它事件時一次送出
+        // 送出的是錄製時產生的文字 (已反映鍵盤配置、Caps Lock 與輸入法的死鍵)，沒有文字的按鍵照常逐鍵模擬
+        if is_unicode_injection {
+            match (event.event_type, &event.text) {
+                (KeyPress(key), Some(text)) if !has_held_modifier(playback) => {
+                    if pending_text.is_empty() { wait_delay(playback, delay); }
+                    pending_text.push((key, text.clone()));
+                    absorbed_keys.push(key);
+                    continue;
+                }
+                (KeyRelease(key), _) if absorbed_keys.contains(&key) => { absorbed_keys.retain(|absorbed| *absorbed != key); continue; }
+                _ => {}
+            }
+            flush_pending_text(&mut pending_text);
+        }
+
+        // 滑鼠插補時，等待時間平均分給中間的移動 (先等待第一段)
+        let steps = if matches!(event.event_type, MouseMove { .. }) && last_position.is_some() { interpolation_steps } else { 0 };
+        let step_delay = delay / (steps + 1);
+
+        wait_delay(playback, step_delay);
+
+        // 連續太多沒有間隔的事件時短暫休息，避免長時間佔滿 CPU
+        if is_cpu_friendly {
+            zero_gap_run = if delay.is_zero() { zero_gap_run + 1 } else { 0 };
+            if zero_gap_run >= CPU_FRIENDLY_BURST { zero_gap_run = 0; playback.clock.sleep(Duration::from_millis(1)); }
+        }
+
+        // 根據事件類型直接模擬，不再傳入延遲時間
+        match event.event_type {
+            KeyPress(key) => {
+                // 按住修飾鍵時，確保修飾鍵至少按住一段時間後才送出後面的按鍵
+                if key_modifier(key).is_none() && !min_modifier_hold.is_zero() && has_held_modifier(playback) {
+                    let held = last_modifier_press.map(|pressed| playback.clock.monotonic().saturating_sub(pressed)).unwrap_or_default();
+                    if held < min_modifier_hold { playback.clock.sleep(min_modifier_hold - held); }
+                }
+                if key_modifier(key).is_some() { last_modifier_press = Some(playback.clock.monotonic()); }
+
+                _play_key_press(key);
+                track_held_key(playback, key, true);
+                // 修飾鍵按下後稍等一下，避免系統先處理到後面的按鍵 (例如 Ctrl+C 少了 Ctrl)
+                if key_modifier(key).is_some() && !modifier_settle.is_zero() { playback.clock.sleep(modifier_settle); }
+            }
+            KeyRelease(key) => { _play_key_release(key); track_held_key(playback, key, false); }
+            MouseMove { x, y } => {
+                let (x, y) = transform.map_or((x, y), |transform| transform.apply(x, y));
+                let (x, y) = safe_area.map_or((x, y), |(min, max_x, max_y)| (x.clamp(min, max_x), y.clamp(min, max_y)));
+                // 與上一次送出的位置距離太近時略過 (等待時間照常計算)
+                let is_skip = last_position.is_some_and(|(last_x, last_y)| (x - last_x).hypot(y - last_y) < move_threshold);
+                if is_skip { wait_delay(playback, step_delay * steps); continue; }
+
+                // 在上一次送出的位置與目標之間插入中間的移動 (途中收到停止訊號時直接移到目標)
+                if let Some((last_x, last_y)) = last_position {
+                    for step in 1..=steps {
+                        if playback.is_stopped() { break; }
+                        let ratio = step as f64 / (steps + 1) as f64;
+                        _play_mouse_move(last_x + (x - last_x) * ratio, last_y + (y - last_y) * ratio);
+                        wait_delay(playback, step_delay);
+                    }
+                }
+
+                _play_mouse_move(x, y);
+                last_position = Some((x, y));
+            }
+            ButtonPress(button) | ButtonRelease(button) => { _play_button(event.event_type, button); }
+            Wheel { delta_x, delta_y } => { _play_wheel(delta_x, delta_y); }
+        }
+    }
+
+    if !is_stop {
+        flush_pending_text(&mut pending_text);
+        is_stop = run_branches_at(playback, events.len());
+    }
+
+    if is_stop { release_held_keys(playback); }
+    if unsupported_count > 0 { println!("[Warning] 略過了 {} 個這個平台無法模擬的事件: {:?}", unsupported_count, capabilities); }
+
+    let sent: Vec<StoredEvent> = events[..sent_count].iter().filter(|event| event.enabled && playback.options.filter.includes(&event.event_type)).cloned().collect();
+    *lock_or_recover(&LAST_PLAYBACK_TYPED) = if is_typing_only(&sent) { Some(typed_text(&sent, false).chars().count()) } else { None };
+
+    is_stop
+}
+
+/// 是否只有單純的打字 (只有會打出字元的按鍵、Shift、Caps Lock 與 Backspace，沒有其它快捷鍵或滑鼠事件)
+/// # 參數
+/// - `events` - &[StoredEvent]
+/// # 返回值
+/// - bool
+fn is_typing_only(events: &[StoredEvent]) -> bool {
+
+    !events.is_empty() && events.iter().all(|event| match event.event_type {
+        KeyPress(key) | KeyRelease(key) => matches!(key, Key::CapsLock | Key::Backspace) || key_modifier(key) == Some(Modifier::Shift) || key_char(key, false).is_some(),
+        _ => false,
+    })
+}
+
+/// 以 Backspace 刪除上一次回放打出的文字 (盡力而為：只支援單純打字的回放，且假設游標仍停在打完的位置)
+/// # 返回值
+/// - Result<usize, String> - 送出的 Backspace 數量；上一次回放不是單純打字時返回錯誤
+pub fn undo_last_playback() -> Result<usize, String> {
+
+    let count = lock_or_recover(&LAST_PLAYBACK_TYPED).take().ok_or("上一次回放不是單純的打字，無法復原")?;
+
+    for _ in 0..count {
+        _play_key_press(Key::Backspace);
+        _play_key_release(Key::Backspace);
+        sleep(Duration::from_millis(2));
+    }
+
+    Ok(count)
+}
+
+/// 暫停回放期間持續等待 (每次短暫休息後檢查停止訊號)，繼續後從同一個事件接著回放，之後的間隔以繼續的時間重新計算
+/// # 參數
+/// - `playback` - &Playback
+/// # 返回值
+/// - bool - 等待途中被停止時返回 false
+fn wait_while_paused(playback: &Playback) -> bool {
+
+    while PLAYBACK_PAUSED.load(Ordering::SeqCst) {
+        if playback.is_stopped() { return false; }
+        sleep(Duration::from_millis(20));
+    }
+
+    true
+}
+
+/// 暫停所有進行中的回放 (發送 "PlaybackPaused" 事件)
+/// # 返回值
+/// - bool - 原本沒有暫停時返回 true
+pub fn pause_playback() -> bool {
+    let is_changed = !PLAYBACK_PAUSED.swap(true, Ordering::SeqCst);
+    if is_changed { emit_event("PlaybackPaused", 0); }
+    is_changed
+}
+
+/// 繼續暫停中的回放 (發送 "PlaybackResumed" 事件)
+/// # 返回值
+/// - bool - 原本有暫停時返回 true
+pub fn resume_playback() -> bool {
+    let is_changed = PLAYBACK_PAUSED.swap(false, Ordering::SeqCst);
+    if is_changed { emit_event("PlaybackResumed", 0); }
+    is_changed
+}
+
+/// 等待事件之間的間隔 (開啟高精度計時時，最後一小段改用忙碌等待，期間仍會檢查停止訊號)
+/// # 參數
+/// - `playback` - &Playback
+/// - `delay` - Duration
+fn wait_delay(playback: &Playback, delay: Duration) {
+
+    if !HIGH_PRECISION_TIMING.load(Ordering::SeqCst) { playback.clock.sleep(delay); return; }
+
+    let deadline = Instant::now() + delay;
+    if delay > SPIN_THRESHOLD { playback.clock.sleep(delay - SPIN_THRESHOLD); }
+
+    let is_cpu_friendly = PLAYBACK_CPU_FRIENDLY.load(Ordering::SeqCst);
+
+    while Instant::now() < deadline {
+        if playback.is_stopped() { return; }
+        if is_cpu_friendly { std::thread::yield_now(); } else { std::hint::spin_loop(); }
+    }
+}
+
+/// 事件是否會送出 (啟用、在回放種類內且所屬的軌道沒有靜音)
+/// # 參數
+/// - `playback` - &Playback
+/// - `event` - &StoredEvent
+/// # 返回值
+/// - bool
+fn is_played(playback: &Playback, event: &StoredEvent) -> bool {
+    event.enabled && playback.options.filter.includes(&event.event_type) && !playback.options.muted.mutes(&event.event_type)
+}
+
+/// 計算每個事件送出前的等待時間 (回放迴圈與剩餘時間的估算共用)
+/// - 第一個事件等待錄製開始到該事件的時間 (設定跳過開頭空白時為 0)，之後為平滑後的間隔 (有單調時間時以單調時間計算)
+/// - 不送出的事件依設定保留或略過等待時間；無法模擬的事件保留原本的等待時間
+/// - 套用固定鍵盤間隔與雙擊的縮短間隔
+/// # 參數
+/// - `playback` - &Playback
+/// # 返回值
+/// - Vec<Duration> - 與事件一一對應
+fn playback_delays(playback: &Playback) -> Vec<Duration> {
+
+    let events = &playback.events;
+    let Some(first) = events.first() else { return Vec::new(); };
+
+    // 跳過開頭空白時，第一個事件會在開始後立刻觸發；否則保留錄製開始到第一個事件之間的等待
+    let leading_delay = match playback.started_at {
+        Some(started_at) if !SKIP_LEADING_GAP.load(Ordering::SeqCst) => first.elapsed.unwrap_or_else(|| first.time.duration_since(started_at).unwrap_or_default()),
+        _ => Duration::ZERO,
+    };
+
+    // 計算與上一個事件之間的時間差
+    let gaps: Vec<Duration> = events.windows(2).map(|pair| pair[1].gap_since(&pair[0])).collect();
+    let gaps = smooth_gaps(&gaps, TIMING_SMOOTHING_WINDOW.load(Ordering::SeqCst));
+
+    let is_collapse = COLLAPSE_DISABLED_GAPS.load(Ordering::SeqCst);
+    let is_preserve_doubleclicks = PRESERVE_DOUBLECLICKS.load(Ordering::SeqCst);
+    let capabilities = simulate_capabilities();
+
+    std::iter::once(leading_delay).chain(gaps).zip(events.iter().enumerate()).map(|(delay, (index, event))| {
+
+        if !is_played(playback, event) { return if is_collapse { Duration::ZERO } else { delay }; }
+        if !capabilities.supports(&event.event_type) { return delay; }
+
+        // 設定固定鍵盤間隔時，鍵盤事件以固定間隔取代原本的時間差
+        let delay = match playback.options.fixed_key_gap {
+            Some(gap) if index > 0 && matches!(event.event_type, KeyPress(_) | KeyRelease(_)) => gap,
+            _ => delay,
+        };
+
+        // 雙擊的事件縮短間隔，確保系統仍判斷為雙擊 (不會變成兩次單擊)
+        if event.double_click && is_preserve_doubleclicks { delay.min(DOUBLE_CLICK_STEP) } else { delay }
+    }).collect()
+}
+
+/// 以移動平均平滑事件之間的間隔，並等比例調整回原本的總時間
+/// # 參數
+/// - `gaps` - &[Duration] 事件之間的間隔
+/// - `window` - usize 移動平均的範圍 (小於 2 時不處理)
+/// # 返回值
+/// - Vec<Duration>
+fn smooth_gaps(gaps: &[Duration], window: usize) -> Vec<Duration> {
+
+    if window < 2 || gaps.len() < 2 { return gaps.to_vec(); }
+
+    let half = window / 2;
+    let averaged: Vec<f64> = (0..gaps.len()).map(|index| {
+        let start = index.saturating_sub(half);
+        let end = (index + half + 1).min(gaps.len());
+        gaps[start..end].iter().map(|gap| gap.as_secs_f64()).sum::<f64>() / (end - start) as f64
+    }).collect();
+
+    let total: f64 = gaps.iter().map(|gap| gap.as_secs_f64()).sum();
+    let averaged_total: f64 = averaged.iter().sum();
+    if averaged_total <= 0.0 { return gaps.to_vec(); }
+
+    let scale = total / averaged_total;
+    averaged.iter().map(|gap| Duration::from_secs_f64(gap * scale)).collect()
+}
+
+/// 回放錄製的鍵盤事件 (等待回放結束)
+/// # 返回值
+/// - PlaybackOutcome - 沒有可回放的事件 / 回放完成 / 中途被停止
+pub fn playback() -> PlaybackOutcome { playback_with(PlaybackOptions::default()) }
+
+/// 只回放鍵盤事件，略過滑鼠移動 / 按鍵 / 滾輪 (等待回放結束)
+/// # 返回值
+/// - PlaybackOutcome - 沒有可回放的事件 / 回放完成 / 中途被停止
+pub fn playback_keyboard_only() -> PlaybackOutcome {
+    playback_with(PlaybackOptions { filter: PlaybackFilter::KeyboardOnly, ..Default::default() })
+}
+
+/// 只回放滑鼠事件 (移動 / 按鍵 / 滾輪)，略過鍵盤事件 (等待回放結束)
+/// # 返回值
+/// - PlaybackOutcome - 沒有可回放的事件 / 回放完成 / 中途被停止
+pub fn playback_mouse_only() -> PlaybackOutcome {
+    playback_with(PlaybackOptions { filter: PlaybackFilter::MouseOnly, ..Default::default() })
+}
+
+/// 只回放距離錄製開始指定時間範圍內的事件 (等待回放結束)
+/// # 參數
+/// - `start_ms` - u64 開始時間 (毫秒)
+/// - `end_ms` - u64 結束時間 (毫秒)
+/// # 返回值
+/// - bool - 範圍不正確或範圍內沒有事件時返回 false
+pub fn playback_window(start_ms: u64, end_ms: u64) -> bool {
+    remember_playback(LastPlayback::Window(start_ms, end_ms));
+    default_handle().play_window(start_ms, end_ms)
+}
+
+/// 以固定的間隔回放鍵盤事件 (滑鼠事件維持原本的時間，等待回放結束)
+/// # 參數
+/// - `gap_ms` - u64 鍵盤事件之間的間隔（毫秒）
+/// # 返回值
+/// - PlaybackOutcome - 沒有可回放的事件 / 回放完成 / 中途被停止
+pub fn playback_fixed_key_gap(gap_ms: u64) -> PlaybackOutcome {
+    playback_with(PlaybackOptions { fixed_key_gap: Some(Duration::from_millis(gap_ms)), ..Default::default() })
+}
+
+/// 以指定的選項回放預設錄製器的錄製內容，並記錄成上一次的回放方式 (等待回放結束)
+/// # 參數
+/// - `options` - PlaybackOptions
+/// # 返回值
+/// - PlaybackOutcome
+fn playback_with(options: PlaybackOptions) -> PlaybackOutcome {
+    remember_playback(LastPlayback::Record(options));
+    default_handle().play_with(options)
+}
+
+/// 記錄上一次回放的方式
+/// # 參數
+/// - `last` - LastPlayback
+fn remember_playback(last: LastPlayback) {
+    *lock_or_recover(&LAST_PLAYBACK) = Some(last);
+}
+
+/// 以相同的方式重新執行上一次的回放 (單一錄製內容、時間範圍、暫存內容或播放清單；等待回放結束)
+/// # 返回值
+/// - Result<(), String> - 還沒有回放過、已有回放進行中或回放失敗時返回錯誤
+pub fn repeat_last_playback() -> Result<(), String> {
+
+    let last = lock_or_recover(&LAST_PLAYBACK).clone().ok_or("還沒有回放過")?;
+
+    // 連續按下快速鍵時只執行一次 (回放註冊之前也不會重複開始)
+    if !lock_or_recover(&PLAYBACKS).is_empty() || IS_REPEATING.swap(true, Ordering::SeqCst) { return Err("回放進行中".to_string()); }
+
+    let result = match last {
+        LastPlayback::Record(options) => { default_handle().play_with(options); Ok(()) }
+        LastPlayback::Window(start_ms, end_ms) => { default_handle().play_window(start_ms, end_ms); Ok(()) }
+        LastPlayback::Spawn => { default_handle().spawn_play(); Ok(()) }
+        LastPlayback::Slot(name) => playback_slot(&name).map(|_| ()),
+        LastPlayback::Playlist(paths, gap_ms) => playback_playlist(&paths, gap_ms),
+    };
+
+    IS_REPEATING.store(false, Ordering::SeqCst);
+    result
+}
+
+/// 在新執行緒回放錄製的鍵盤事件 (不等待回放結束，可同時進行多個回放)
+/// # 返回值
+/// - Option<u64> - 回放編號，沒有可回放的事件時返回 None
+pub fn spawn_playback() -> Option<u64> {
+    remember_playback(LastPlayback::Spawn);
+    default_handle().spawn_play()
+}
+
+/// 立即送出單一事件 (不等待時間，除錯用；測試模式下只會記錄輸出)
+/// # 參數
+/// - `index` - usize 事件位置
+/// # 返回值
+/// - Result<(), String> - 位置超出範圍時返回錯誤
+pub fn play_event(index: usize) -> Result<(), String> {
+
+    let recorder = default_handle().lock();
+    let event_type = recorder.events.get(index).map(|event| event.event_type).ok_or_else(|| format!("位置超出範圍: {} (共 {} 個事件)", index, recorder.events.len()))?;
+    drop(recorder);
+
+    play_single(event_type);
+
+    Ok(())
+}
+
+/// 送出單一事件並更新按住中的按鍵
+/// # 參數
+/// - `event_type` - EventType
+fn play_single(event_type: EventType) {
+
+    match event_type {
+        KeyPress(key) => { _play_key_press(key); update_held_keys(&HELD_KEYS, key, true); }
+        KeyRelease(key) => { _play_key_release(key); update_held_keys(&HELD_KEYS, key, false); }
+        ButtonPress(button) | ButtonRelease(button) => { _play_button(event_type, button); }
+        MouseMove { x, y } => { _play_mouse_move(x, y); }
+        Wheel { delta_x, delta_y } => { _play_wheel(delta_x, delta_y); }
+    }
+}
+
+/// 立即送出一個按鍵組合 (依序按下修飾鍵與按鍵，再以相反的順序放開；修飾鍵按下後依設定稍等一下)
+/// # 參數
+/// - `combo` - &str 按鍵組合 (例如 `CTRL+SHIFT+KeyT`，也可以寫成 `CTRL+SHIFT+T`)
+/// # 返回值
+/// - Result<(), String> - 無法辨識時返回錯誤 (包含無法辨識的部分)
+pub fn simulate_combo(combo: &str) -> Result<(), String> {
+
+    let combo = parse_combo(combo)?;
+    let modifier_settle = Duration::from_millis(MODIFIER_SETTLE_MS.load(Ordering::SeqCst));
+
+    let mut keys: Vec<Key> = Vec::new();
+    for key in combo.modifiers.iter().map(|modifier| modifier_key(*modifier)).chain(std::iter::once(combo.key)) {
+        if !keys.contains(&key) { keys.push(key); }
+    }
+
+    for key in keys.iter() {
+        play_single(KeyPress(*key));
+        if key_modifier(*key).is_some() && !modifier_settle.is_zero() { sleep(modifier_settle); }
+    }
+
+    for key in keys.iter().rev() { play_single(KeyRelease(*key)); }
+
+    Ok(())
+}
+
+/// 展開呼叫的暫存錄製內容 (遞迴展開，呼叫鏈中出現重複的名稱時視為循環並返回錯誤)
+/// - 被呼叫的內容從呼叫位置的事件時間開始，其後的事件依被呼叫內容的長度往後延
+/// # 參數
+/// - `events` - Vec<StoredEvent> 事件
+/// - `calls` - &[SlotCall] 呼叫的位置 (依位置排序)
+/// - `stack` - &mut Vec<String> 目前的呼叫鏈
+/// # 返回值
+/// - Result<Vec<StoredEvent>, String>
+fn expand_slot_calls(events: Vec<StoredEvent>, calls: &[SlotCall], stack: &mut Vec<String>) -> Result<Vec<StoredEvent>, String> {
+    expand_slot_calls_with_positions(events, calls, stack).map(|(events, _)| events)
+}
+
+/// 展開呼叫的暫存錄製內容，並返回原本每個事件在展開後的位置
+/// # 參數
+/// - `events` - Vec<StoredEvent> 事件
+/// - `calls` - &[SlotCall] 呼叫的位置 (依位置排序)
+/// - `stack` - &mut Vec<String> 目前的呼叫鏈
+/// # 返回值
+/// - Result<(Vec<StoredEvent>, Vec<usize>), String> - 展開後的事件、原本第 i 個事件展開後的位置 (最後一個為展開後的事件數量)
+fn expand_slot_calls_with_positions(events: Vec<StoredEvent>, calls: &[SlotCall], stack: &mut Vec<String>) -> Result<(Vec<StoredEvent>, Vec<usize>), String> {
+
+    if calls.is_empty() { let positions = (0..=events.len()).collect(); return Ok((events, positions)); }
+
+    let end_time = events.last().map(|event| event.time).unwrap_or(UNIX_EPOCH);
+    let mut expanded = Vec::with_capacity(events.len());
+    let mut positions = Vec::with_capacity(events.len() + 1);
+    let mut shift = Duration::ZERO;
+
+    for index in 0..=events.len() {
+
+        let base_time = events.get(index).map_or(end_time, |event| event.time);
+
+        for call in calls.iter().filter(|call| call.index == index) {
+
+            if stack.contains(&call.name) { return Err(format!("錄製內容循環呼叫: {} -> {}", stack.join(" -> "), call.name)); }
+
+            let slot = lock_or_recover(&SLOTS).get(&call.name).cloned().ok_or_else(|| format!("找不到錄製內容: {}", call.name))?;
+
+            stack.push(call.name.clone());
+            let child = expand_slot_calls(to_pixels(slot.events, slot.info.coordinate_mode)?, &slot.info.calls, stack)?;
+            stack.pop();
+
+            let Some(first_time) = child.first().map(|event| event.time) else { continue; };
+            let call_start = base_time + shift;
+            let mut duration = Duration::ZERO;
+
+            for event in child {
+                let offset = event.time.duration_since(first_time).unwrap_or_default();
+                duration = offset;
+                expanded.push(StoredEvent { time: call_start + offset, elapsed: None, ..event });
+            }
+
+            shift += duration;
+        }
+
+        positions.push(expanded.len());
+
+        if let Some(event) = events.get(index) {
+            expanded.push(StoredEvent { time: event.time + shift, elapsed: None, ..event.clone() });
+        }
+    }
+
+    Ok((expanded, positions))
+}
+
+/// 依序執行指定位置的分支
+/// # 參數
+/// - `playback` - &Playback 目前的回放
+/// - `position` - usize 展開後的事件位置
+/// # 返回值
+/// - bool - 回放是否被中途停止
+fn run_branches_at(playback: &Playback, position: usize) -> bool {
+    playback.branches.iter().filter(|(branch_position, _)| *branch_position == position).any(|(_, branch)| run_branch(playback, branch))
+}
+
+/// 回放到分支的位置時，依按鍵目前是否按住回放其中一個暫存的錄製內容 (等待回放結束)
+/// - 按鍵狀態取自 HELD_KEYS：由一直在執行的 rdev 監聽執行緒依實體按鍵更新，回放送出的按鍵也會更新 (回放中按下尚未放開的按鍵視為按住)
+/// - 分支內容裡的呼叫會展開，但不會再判斷分支內容自己的分支，避免無限循環
+/// - 與目前的回放共用停止訊號與最長執行時間
+/// # 參數
+/// - `playback` - &Playback 目前的回放
+/// - `branch` - &SlotBranch
+/// # 返回值
+/// - bool - 回放是否被中途停止
+fn run_branch(playback: &Playback, branch: &SlotBranch) -> bool {
+
+    let is_held = lock_or_recover(&HELD_KEYS).contains(&branch.key);
+    let Some(name) = (if is_held { &branch.then_slot } else { &branch.else_slot }) else { return false; };
+
+    let Some(slot) = lock_or_recover(&SLOTS).get(name).cloned() else { emit_event("SlotCallFailed", format!("找不到錄製內容: {}", name)); return false; };
+
+    let events = match to_pixels(slot.events, slot.info.coordinate_mode).and_then(|events| expand_slot_calls(events, &slot.info.calls, &mut vec![name.clone()])) {
+        Ok(events) => events,
+        Err(error) => { emit_event("SlotCallFailed", error); return false; }
+    };
+
+    let (id, branch_playback) = register_playback(events, None, playback.stop.clone(), playback.recorder_stop.clone(), playback.clock.clone(), playback.options, Vec::new());
+    let is_stop = run_playback(&branch_playback);
+    unregister_playback(id);
+
+    is_stop || playback.is_stopped()
+}
+
+/// 開始逐步回放 (以目前的錄製內容建立，停用的事件會略過)
+/// # 返回值
+/// - usize - 可逐步送出的事件數量
+pub fn step_mode_begin() -> usize {
+
+    let events: Vec<StoredEvent> = default_handle().events().into_iter().filter(|event| event.enabled).collect();
+    let count = events.len();
+    *lock_or_recover(&STEP_SESSION) = Some(StepSession { events, index: 0 });
+
+    count
+}
+
+/// 送出逐步回放的下一個事件，並發送 "StepIndex" 事件 (已送出的位置)
+/// # 返回值
+/// - bool - 還有未送出的事件時返回 true (尚未開始逐步回放時返回 false)
+pub fn step_next() -> bool {
+
+    let mut session = lock_or_recover(&STEP_SESSION);
+    let Some(step) = session.as_mut() else { return false; };
+    let Some(event_type) = step.events.get(step.index).map(|event| event.event_type) else { return false; };
+
+    let index = step.index;
+    step.index += 1;
+    let has_more = step.index < step.events.len();
+    drop(session);
+
+    play_single(event_type);
+    emit_event("StepIndex", index);
+
+    has_more
+}
+
+/// 回到逐步回放的開頭
+/// # 返回值
+/// - bool - 有進行中的逐步回放時返回 true
+pub fn step_reset() -> bool {
+
+    match lock_or_recover(&STEP_SESSION).as_mut() {
+        Some(step) => { step.index = 0; true }
+        None => false,
+    }
+}
+
+/// 停止指定編號的回放
+/// # 參數
+/// - `id` - u64 回放編號
+/// # 返回值
+/// - bool - 找到該回放並送出停止訊號時返回 true
+pub fn stop_playback(id: u64) -> bool {
+
+    match lock_or_recover(&PLAYBACKS).get(&id) {
+        Some(playback) => { playback.stop.store(true, Ordering::SeqCst); true }
+        None => false,
+    }
+}
+
+/// 等待一段時間，期間若收到任一個停止回放訊號則提前結束
+/// # 參數
+/// - `duration` - Duration 等待時間
+/// - `stops` - &[&AtomicBool] 停止回放訊號
+/// # 返回值
+/// - bool - 如果等待途中被停止，返回 true；否則返回 false
+fn wait_unless_stopped(duration: Duration, stops: &[&AtomicBool]) -> bool {
+
+    let step = Duration::from_millis(50);
+    let mut remaining = duration;
+    let is_stopped = || stops.iter().any(|stop| stop.load(Ordering::SeqCst));
+
+    while !remaining.is_zero() {
+        if is_stopped() { return true; }
+        let wait = remaining.min(step);
+        sleep(wait);
+        remaining -= wait;
+    }
+
+    is_stopped()
+}
+
+/// 依序回放多個錄製檔案 (鎖定鍵依各檔案錄製時的狀態切換，結束後還原；靜音的種類不回放)
+/// # 參數
+/// - `paths` - &[String] 錄製檔案路徑
+/// - `gap_ms` - u64 每個檔案之間的間隔（毫秒）
+/// # 返回值
+/// - Result<(), String> - 載入失敗時返回錯誤（包含失敗的檔案）
+pub fn playback_playlist(paths: &[String], gap_ms: u64) -> Result<(), String> {
+
+    remember_playback(LastPlayback::Playlist(paths.to_vec(), gap_ms));
+
+    let handle = default_handle();
+    let recorder_stop = handle.inner.stop_playback.clone();
+    recorder_stop.store(false, Ordering::SeqCst);
+
+    // 所有檔案共用同一個停止訊號與最長執行時間
+    let stop = Arc::new(AtomicBool::new(false));
+    let is_finished = start_runtime_watchdog(stop.clone());
+
+    let options = PlaybackOptions { muted: handle.lock().muted_tracks, ..Default::default() };
+    let mut original_locks = None;
+    let mut result = Ok(false);
+
+    for (index, path) in paths.iter().enumerate() {
+
+        if index > 0 && wait_unless_stopped(Duration::from_millis(gap_ms), &[&stop, &recorder_stop]) { result = Ok(true); break; }
+
+        let (events, started_at, info) = match load_playlist_file(path) {
+            Ok(loaded) => loaded,
+            Err(error) => { result = Err(error); break; }
+        };
+        emit_event("PlaylistProgress", PlaylistProgress { index, total: paths.len(), path: path.clone() });
+
+        // 只保留第一次切換前的狀態，才能還原成使用者原本的鎖定鍵
+        let locks = sync_lock_state(info.lock_state);
+        if original_locks.is_none() { original_locks = locks; }
+
+        let (id, playback) = register_playback(events, Some(started_at), stop.clone(), recorder_stop.clone(), handle.inner.clock.clone(), options, Vec::new());
+        let is_stop = run_playback(&playback);
+        unregister_playback(id);
+
+        if is_stop { result = Ok(true); break; }
+    }
+
+    is_finished.store(true, Ordering::SeqCst);
+    restore_lock_state(original_locks);
+    notify_playback_complete(result != Ok(false));
+
+    result.map(|_| ())
+}
+
+/// 載入播放清單中的一個錄製檔案，並把滑鼠座標換回像素
+/// # 參數
+/// - `path` - &str 錄製檔案路徑
+/// # 返回值
+/// - Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> - 載入或換算失敗時返回錯誤（包含失敗的檔案）
+fn load_playlist_file(path: &str) -> Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> {
+
+    let (events, started_at, info) = FileStorage::new(path).load_with_info().map_err(|error| format!("載入 {} 失敗: {}", path, error))?;
+    let events = to_pixels(events, info.coordinate_mode).map_err(|error| format!("載入 {} 失敗: {}", path, error))?;
+
+    Ok((events, started_at, info))
+}
+
+/// 回放暫存的錄製內容 (等待回放結束，不會取代目前的錄製內容)
+/// # 參數
+/// - `name` - &str 名稱
+/// # 返回值
+/// - Result<PlaybackOutcome, String> - 找不到該名稱或展開呼叫的錄製內容失敗時返回錯誤
+pub fn playback_slot(name: &str) -> Result<PlaybackOutcome, String> {
+
+    remember_playback(LastPlayback::Slot(name.to_string()));
+
+    let slot = lock_or_recover(&SLOTS).get(name).cloned().ok_or_else(|| format!("找不到錄製內容: {}", name))?;
+    let events = expand_slot_calls(to_pixels(slot.events, slot.info.coordinate_mode)?, &slot.info.calls, &mut vec![name.to_string()])?;
+    if events.is_empty() { emit_event("empty-recording", 0); return Ok(PlaybackOutcome::Empty); }
+
+    let handle = default_handle();
+    handle.inner.stop_playback.store(false, Ordering::SeqCst);
+
+    let original_locks = sync_lock_state(slot.info.lock_state);
+    let stop = Arc::new(AtomicBool::new(false));
+    let is_finished = start_runtime_watchdog(stop.clone());
+    let (id, playback) = register_playback(events, slot.started_at, stop, handle.inner.stop_playback.clone(), handle.inner.clock.clone(), PlaybackOptions::default(), Vec::new());
+    let is_stop = run_playback(&playback);
+    is_finished.store(true, Ordering::SeqCst);
+    unregister_playback(id);
+    restore_lock_state(original_locks);
+    notify_playback_complete(is_stop);
+
+    Ok(if is_stop { PlaybackOutcome::Stopped } else { PlaybackOutcome::Completed })
+}
+
+/// 排程在指定時間回放 (可同時有多個排程)
+/// # 參數
+/// - `epoch_ms` - u64 執行時間 (Unix 時間，毫秒，時間已經過去時立即執行)
+/// - `slot` - Option<String> 要回放的暫存錄製內容 (None 表示執行時的錄製內容)
+/// # 返回值
+/// - Result<ScheduleInfo, String> - 找不到暫存的錄製內容時返回錯誤
+pub fn schedule_playback_at(epoch_ms: u64, slot: Option<String>) -> Result<ScheduleInfo, String> {
+
+    if let Some(name) = &slot {
+        if !lock_or_recover(&SLOTS).contains_key(name) { return Err(format!("找不到錄製內容: {}", name)); }
+    }
+
+    let info = ScheduleInfo { id: NEXT_SCHEDULE_ID.fetch_add(1, Ordering::SeqCst), epoch_ms, slot };
+    let cancel = Arc::new(AtomicBool::new(false));
+    lock_or_recover(&SCHEDULED).insert(info.id, (info.clone(), cancel.clone()));
+
+    let target_time = UNIX_EPOCH + Duration::from_millis(epoch_ms);
+    let delay = target_time.duration_since(default_handle().inner.clock.now()).unwrap_or_default();
+    let schedule = info.clone();
+
+    spawn(move || {
+
+        let is_cancel = wait_unless_stopped(delay, &[&cancel]);
+        lock_or_recover(&SCHEDULED).remove(&schedule.id);
+        if is_cancel { return; }
+
+        emit_event("ScheduledPlaybackStarted", schedule.clone());
+
+        match &schedule.slot {
+            Some(name) => if let Err(error) = playback_slot(name) { emit_event("ScheduledPlaybackFailed", error); },
+            None => { playback(); }
+        }
+    });
+
+    Ok(info)
+}
+
+/// 列出尚未執行的排程回放 (依執行時間排序)
+/// # 返回值
+/// - Vec<ScheduleInfo>
+pub fn list_schedules() -> Vec<ScheduleInfo> {
+
+    let mut schedules: Vec<ScheduleInfo> = lock_or_recover(&SCHEDULED).values().map(|(info, _)| info.clone()).collect();
+    schedules.sort_by_key(|info| (info.epoch_ms, info.id));
+
+    schedules
+}
+
+/// 取消指定的排程回放
+/// # 參數
+/// - `id` - u64 排程編號
+/// # 返回值
+/// - bool - 有排程被取消時返回 true
+pub fn cancel_schedule(id: u64) -> bool {
+
+    match lock_or_recover(&SCHEDULED).remove(&id) {
+        Some((_, cancel)) => { cancel.store(true, Ordering::SeqCst); true }
+        None => false,
+    }
+}
+
+/// 取消所有尚未執行的排程回放
+/// # 返回值
+/// - bool - 有排程被取消時返回 true
+pub fn cancel_scheduled_playback() -> bool {
+
+    let scheduled: Vec<_> = lock_or_recover(&SCHEDULED).drain().collect();
+    for (_, (_, cancel)) in scheduled.iter() { cancel.store(true, Ordering::SeqCst); }
+
+    !scheduled.is_empty()
+}
+
+/// 緊急停止：停止所有回放與錄製、取消排程並放開所有按住中的按鍵
+/// # 返回值
+/// - usize - 放開的按鍵數量
+pub fn panic_stop() -> usize {
+
+    cancel_scheduled_playback();
+    PLAYBACK_PAUSED.store(false, Ordering::SeqCst);
+
+    for inner in live_handles().iter() {
+        inner.stop_playback.store(true, Ordering::SeqCst);
+        inner.is_listening.store(false, Ordering::SeqCst);
+    }
+
+    for playback in lock_or_recover(&PLAYBACKS).values() { playback.stop.store(true, Ordering::SeqCst); }
+
+    let released = release_all_held_keys();
+    emit_event("panic-stopped", released);
+
+    released
+}
+
+/// 掃描錄製內容中的危險按鍵組合 (例如 Command+Q、Ctrl+Alt+Delete)
+/// - 依錄製內容追蹤按住的修飾鍵；沒有放開事件的錄製內容，修飾鍵只算到下一個一般按鍵為止
+/// # 返回值
+/// - Vec<DangerFinding> - 找到的位置與說明
+pub fn scan_dangerous() -> Vec<DangerFinding> {
+
+    let events = events();
+    let chords = lock_or_recover(&DANGER_CHORDS).clone();
+    let has_releases = events.iter().any(|event| matches!(event.event_type, KeyRelease(_)));
+
+    let mut held_modifiers: Vec<Modifier> = Vec::new();
+    let mut findings = Vec::new();
+
+    for (index, event) in events.iter().enumerate() {
+        match event.event_type {
+            KeyPress(key) => match key_modifier(key) {
+                Some(modifier) => { if !held_modifiers.contains(&modifier) { held_modifiers.push(modifier); } }
+                None => {
+                    for chord in chords.iter().filter(|chord| chord.key == key && chord.modifiers.iter().all(|modifier| held_modifiers.contains(modifier))) {
+                        findings.push(DangerFinding { index, description: format!("按下了危險的按鍵組合: {}", chord.name) });
+                    }
+                    if !has_releases { held_modifiers.clear(); }
+                }
+            },
+            KeyRelease(key) => { if let Some(modifier) = key_modifier(key) { held_modifiers.retain(|held| *held != modifier); } }
+            _ => {}
+        }
+    }
+
+    findings
+}
+
+/// 比較目前的錄製內容與錄製檔案 (以事件類型對齊，時間以第一個事件為起點比較)
+/// # 參數
+/// - `path` - &str 作為基準的錄製檔案
+/// - `timing_tolerance_ms` - Option<u64> 對齊的事件時間差超過此值時視為變更 (None 表示不比較時間)
+/// - `position_tolerance` - Option<f64> 對齊的滑鼠移動座標相差超過此距離時視為變更 (None 表示使用預設值)
+/// # 返回值
+/// - Result<Vec<DiffEntry>, String> - 依位置排序的差異，載入失敗或差異太多無法比較時返回錯誤
+pub fn diff_record(path: &str, timing_tolerance_ms: Option<u64>, position_tolerance: Option<f64>) -> Result<Vec<DiffEntry>, String> {
+    let (baseline, _) = FileStorage::new(path).load()?;
+    diff_events(&events(), &baseline, timing_tolerance_ms, position_tolerance.unwrap_or(DIFF_POSITION_TOLERANCE))
+}
+
+/// 比較兩組事件 (先略過相同的開頭與結尾，其餘以最長共同子序列對齊；滑鼠移動只以種類對齊，座標另外比較)
+/// # 參數
+/// - `current` - &[StoredEvent] 目前的事件
+/// - `baseline` - &[StoredEvent] 作為基準的事件
+/// - `timing_tolerance_ms` - Option<u64> 對齊的事件時間差超過此值時視為變更 (None 表示不比較時間)
+/// - `position_tolerance` - f64 對齊的滑鼠移動座標相差超過此距離時視為變更
+/// # 返回值
+/// - Result<Vec<DiffEntry>, String> - 依位置排序的差異，需要對齊的事件太多 (超過 MAX_DIFF_CELLS) 時返回錯誤
+fn diff_events(current: &[StoredEvent], baseline: &[StoredEvent], timing_tolerance_ms: Option<u64>, position_tolerance: f64) -> Result<Vec<DiffEntry>, String> {
+
+    let is_same = |index: usize, baseline_index: usize| is_same_event(&current[index].event_type, &baseline[baseline_index].event_type);
+
+    let prefix = (0..current.len().min(baseline.len())).take_while(|index| is_same(*index, *index)).count();
+    let suffix = (0..(current.len() - prefix).min(baseline.len() - prefix)).take_while(|offset| is_same(current.len() - 1 - offset, baseline.len() - 1 - offset)).count();
+    let (rows, columns) = (current.len() - prefix - suffix, baseline.len() - prefix - suffix);
+
+    if rows.saturating_mul(columns) > MAX_DIFF_CELLS {
+        return Err(format!("差異太多無法比較: {} 與 {} 個不同的事件 (上限 {} 組)", rows, columns, MAX_DIFF_CELLS));
+    }
+
+    let offsets = |events: &[StoredEvent]| -> Vec<u64> {
+        let first = events.first().map(|event| event.time);
+        events.iter().map(|event| first.and_then(|first| event.time.duration_since(first).ok()).unwrap_or_default().as_millis() as u64).collect()
+    };
+    let (current_offsets, baseline_offsets) = (offsets(current), offsets(baseline));
+
+    // 對齊的事件：時間或滑鼠座標超過容許值時合併成一個變更
+    let compare = |index: usize, baseline_index: usize| -> Option<DiffEntry> {
+
+        let mut changes = Vec::new();
+
+        let difference = current_offsets[index].abs_diff(baseline_offsets[baseline_index]);
+        if timing_tolerance_ms.is_some_and(|tolerance| difference > tolerance) {
+            changes.push(format!("時間相差 {} 毫秒 ({} -> {})", difference, baseline_offsets[baseline_index], current_offsets[index]));
+        }
+
+        if let (MouseMove { x, y }, MouseMove { x: baseline_x, y: baseline_y }) = (current[index].event_type, baseline[baseline_index].event_type) {
+            let distance = (x - baseline_x).hypot(y - baseline_y);
+            if distance > position_tolerance { changes.push(format!("座標相差 {:.1} (({:.1}, {:.1}) -> ({:.1}, {:.1}))", distance, baseline_x, baseline_y, x, y)); }
+        }
+
+        if changes.is_empty() { return None; }
+        Some(DiffEntry { kind: DiffKind::Changed, index: Some(index), baseline_index: Some(baseline_index), description: changes.join("; ") })
+    };
+
+    // 中間不同的部分以最長共同子序列 (LCS) 對齊
+    let width = columns + 1;
+    let mut lengths = vec![0u32; (rows + 1) * width];
+
+    for row in (0..rows).rev() {
+        for column in (0..columns).rev() {
+            lengths[row * width + column] = if is_same(prefix + row, prefix + column) {
+                lengths[(row + 1) * width + column + 1] + 1
+            } else {
+                lengths[(row + 1) * width + column].max(lengths[row * width + column + 1])
+            };
+        }
+    }
+
+    let length = |row: usize, column: usize| lengths[row * width + column];
+
+    let mut entries: Vec<DiffEntry> = (0..prefix).filter_map(|index| compare(index, index)).collect();
+    let (mut row, mut column) = (0, 0);
+    let mut added: Vec<usize> = Vec::new();
+    let mut removed: Vec<usize> = Vec::new();
+
+    // 連續的新增 / 移除兩兩配對成變更，其餘維持新增 / 移除
+    let flush = |added: &mut Vec<usize>, removed: &mut Vec<usize>, entries: &mut Vec<DiffEntry>| {
+        let paired = added.len().min(removed.len());
+        for (index, baseline_index) in added.iter().zip(removed.iter()).take(paired) {
+            let description = format!("{:?} -> {:?}", baseline[*baseline_index].event_type, current[*index].event_type);
+            entries.push(DiffEntry { kind: DiffKind::Changed, index: Some(*index), baseline_index: Some(*baseline_index), description });
+        }
+        for index in added.iter().skip(paired) {
+            entries.push(DiffEntry { kind: DiffKind::Added, index: Some(*index), baseline_index: None, description: format!("{:?}", current[*index].event_type) });
+        }
+        for baseline_index in removed.iter().skip(paired) {
+            entries.push(DiffEntry { kind: DiffKind::Removed, index: None, baseline_index: Some(*baseline_index), description: format!("{:?}", baseline[*baseline_index].event_type) });
+        }
+        added.clear();
+        removed.clear();
+    };
+
+    while row < rows || column < columns {
+
+        let (index, baseline_index) = (prefix + row, prefix + column);
+
+        if row < rows && column < columns && is_same(index, baseline_index) {
+            flush(&mut added, &mut removed, &mut entries);
+            entries.extend(compare(index, baseline_index));
+            row += 1;
+            column += 1;
+        } else if column < columns && (row >= rows || length(row, column + 1) >= length(row + 1, column)) {
+            removed.push(baseline_index);
+            column += 1;
+        } else {
+            added.push(index);
+            row += 1;
+        }
+    }
+
+    flush(&mut added, &mut removed, &mut entries);
+    entries.extend((0..suffix).filter_map(|offset| compare(prefix + rows + offset, prefix + columns + offset)));
+
+    Ok(entries)
+}
+
+/// 比較時是否視為同一個事件 (滑鼠移動只比較種類，重新錄製的滑鼠路徑座標不會完全相同)
+/// # 參數
+/// - `current` - &EventType
+/// - `baseline` - &EventType
+/// # 返回值
+/// - bool
+fn is_same_event(current: &EventType, baseline: &EventType) -> bool {
+
+    match (current, baseline) {
+        (MouseMove { .. }, MouseMove { .. }) => true,
+        _ => current == baseline,
+    }
+}
+
+/// 統計錄製內容中事件之間間隔的分布
+/// # 參數
+/// - `bucket_ms` - u64 每個區間的大小 (毫秒，0 視為 1)
+/// # 返回值
+/// - Vec<(u64, usize)> - (區間起點毫秒數, 間隔數量)，依區間起點排序，只列出有間隔的區間
+pub fn gap_histogram(bucket_ms: u64) -> Vec<(u64, usize)> {
+
+    let bucket_ms = bucket_ms.max(1);
+    let recorder = default_handle().lock();
+    let mut buckets: BTreeMap<u64, usize> = BTreeMap::new();
+
+    for pair in recorder.events.windows(2) {
+        let gap_ms = pair[1].gap_since(&pair[0]).as_millis() as u64;
+        *buckets.entry(gap_ms / bucket_ms * bucket_ms).or_insert(0) += 1;
+    }
+
+    buckets.into_iter().collect()
+}
+
+/// 取得最新一個進行中的回放目前送到第幾個事件
+/// # 返回值
+/// - Option<usize> - 事件位置，沒有進行中的回放時返回 None
+pub fn playback_position() -> Option<usize> {
+    let playbacks = lock_or_recover(&PLAYBACKS);
+    playbacks.iter().max_by_key(|(id, _)| **id).map(|(_, playback)| playback.index.load(Ordering::SeqCst))
+}
+
+/// 取得錄製內容的事件數量
+/// # 返回值
+/// - usize
+pub fn event_count() -> usize { default_handle().lock().events.len() }
+
+/// 取得鍵盤監聽執行緒最後一次的錯誤
+/// # 返回值
+/// - Option<String> - 沒有發生過錯誤時返回 None
+pub fn last_listen_error() -> Option<String> { lock_or_recover(&LAST_LISTEN_ERROR).clone() }
+
+/// 取得錄製器目前的內部狀態與設定 (只讀取，每個鎖只短暫持有，不會同時持有兩個鎖)
+/// # 參數
+/// - `keyboard_thread_alive` - bool 鍵盤監聽執行緒是否還在執行 (由呼叫端檢查)
+/// # 返回值
+/// - DebugState
+pub fn debug_state(keyboard_thread_alive: bool) -> DebugState {
+
+    let inner = &default_handle().inner;
+    let playback_ids = playback_ids();
+    let (coalesce_taps, tap_threshold_ms) = crate::storage::coalesce_taps();
+
+    let settings = DebugSettings {
+        test_mode: TEST_MODE.load(Ordering::SeqCst),
+        skip_leading_gap: SKIP_LEADING_GAP.load(Ordering::SeqCst),
+        simulate_retries: SIMULATE_RETRIES.load(Ordering::SeqCst),
+        escape_aborts: ESCAPE_ABORTS.load(Ordering::SeqCst),
+        timing_smoothing_window: TIMING_SMOOTHING_WINDOW.load(Ordering::SeqCst),
+        collapse_disabled_gaps: COLLAPSE_DISABLED_GAPS.load(Ordering::SeqCst),
+        record_focus_changes: RECORD_FOCUS_CHANGES.load(Ordering::SeqCst),
+        record_only_app: lock_or_recover(&RECORD_ONLY_APP).clone(),
+        high_precision_timing: HIGH_PRECISION_TIMING.load(Ordering::SeqCst),
+        playback_move_threshold: f64::from_bits(PLAYBACK_MOVE_THRESHOLD_BITS.load(Ordering::SeqCst)),
+        playback_cpu_friendly: PLAYBACK_CPU_FRIENDLY.load(Ordering::SeqCst),
+        modifier_settle_ms: MODIFIER_SETTLE_MS.load(Ordering::SeqCst),
+        min_modifier_hold_ms: MIN_MODIFIER_HOLD_MS.load(Ordering::SeqCst),
+        unicode_injection: UNICODE_INJECTION.load(Ordering::SeqCst),
+        mouse_safe_margin: MOUSE_SAFE_MARGIN.load(Ordering::SeqCst),
+        mouse_interpolation_steps: MOUSE_INTERPOLATION_STEPS.load(Ordering::SeqCst),
+        normalized_mouse_coordinates: NORMALIZED_MOUSE_COORDINATES.load(Ordering::SeqCst),
+        playback_priority: if PLAYBACK_HIGH_PRIORITY.load(Ordering::SeqCst) { "high" } else { "normal" }.to_string(),
+        mouse_transform: *lock_or_recover(&MOUSE_TRANSFORM),
+        capture_media_keys: CAPTURE_MEDIA_KEYS.load(Ordering::SeqCst),
+        preserve_doubleclicks: PRESERVE_DOUBLECLICKS.load(Ordering::SeqCst),
+        coalesce_taps,
+        tap_threshold_ms,
+        max_playback_runtime_ms: MAX_PLAYBACK_RUNTIME_MS.load(Ordering::SeqCst),
+    };
+
+    DebugState {
+        is_listening: inner.is_listening.load(Ordering::SeqCst),
+        stop_playback: inner.stop_playback.load(Ordering::SeqCst),
+        is_playing: !playback_ids.is_empty(),
+        is_playback_paused: PLAYBACK_PAUSED.load(Ordering::SeqCst),
+        event_count: event_count(),
+        playback_ids,
+        schedule_count: lock_or_recover(&SCHEDULED).len(),
+        keyboard_thread_alive,
+        last_listen_error: last_listen_error(),
+        simulate_retry_count: SIMULATE_RETRY_COUNT.load(Ordering::SeqCst),
+        settings,
+    }
+}
+
+/// 計算最新一個進行中的回放預計還要多久結束
+/// # 返回值
+/// - Option<u64> - 剩餘毫秒數，沒有進行中的回放時返回 None
+pub fn playback_remaining_ms() -> Option<u64> {
+
+    let playback = lock_or_recover(&PLAYBACKS).iter().max_by_key(|(id, _)| **id).map(|(_, playback)| playback.clone())?;
+
+    // 以回放迴圈相同的等待時間估算；目前事件的等待時間尚未確定已經過多少，以整段間隔估算
+    let delays = playback_delays(&playback);
+    let index = playback.index.load(Ordering::SeqCst).min(delays.len().checked_sub(1)?);
+    let remaining: Duration = delays[index..].iter().sum();
+
+    Some(remaining.as_millis() as u64)
+}
+
+/// 取得進行中的回放編號
+/// # 返回值
+/// - Vec<u64> - 由小到大排序的回放編號
+pub fn playback_ids() -> Vec<u64> {
+    let mut ids: Vec<u64> = lock_or_recover(&PLAYBACKS).keys().copied().collect();
+    ids.sort();
+    ids
+}
+
+// MARK: 輸入裝置
+/// 取得可指定錄製來源的輸入裝置
+/// - rdev 的事件沒有來源裝置的資訊 (macOS / Windows / Linux 皆是)，目前無法分辨，返回空清單
+/// # 返回值
+/// - Vec<String> - 裝置編號
+pub fn input_devices() -> Vec<String> { Vec::new() }
+
+/// 指定只錄製某個輸入裝置的事件
+/// - rdev 的事件沒有來源裝置的資訊 (macOS / Windows / Linux 皆是)，指定裝置時退回錄製所有裝置，並發送 "input-device-unavailable" 事件 (裝置編號) 提醒
+/// # 參數
+/// - `id` - Option<&str> 裝置編號 (None 表示錄製所有裝置)
+/// # 返回值
+/// - bool - 是否只錄製指定的裝置 (目前的平台都返回 false)
+pub fn set_input_device(id: Option<&str>) -> bool {
+
+    if let Some(id) = id {
+        println!("[Warning] 無法分辨輸入裝置 {}，改為錄製所有裝置的事件", id);
+        emit_event("input-device-unavailable", id.to_string());
+    }
+
+    false
+}
+
+// MARK: 設定
+/// 設定測試模式 (開啟時回放只會記錄輸出，不會真的送出事件)
+/// # 參數
+/// - `enabled` - bool 是否開啟測試模式
+/// # 返回值
+/// - bool - 返回當前是否在測試模式
+pub fn update_test_mode(enabled: bool) -> bool {
+    if enabled { lock_or_recover(&CAPTURED_OUTPUT).clear(); }
+    TEST_MODE.store(enabled, Ordering::SeqCst);
+    TEST_MODE.load(Ordering::SeqCst)
+}
+
+/// 取得測試模式下記錄的模擬輸出
+/// # 返回值
+/// - Vec<String> - 依序記錄的模擬事件
+pub fn test_mode_output() -> Vec<String> {
+    lock_or_recover(&CAPTURED_OUTPUT).clone()
+}
+
+/// 設定回放時是否跳過錄製開始到第一個事件之間的等待
+/// # 參數
+/// - `enabled` - bool 是否跳過 (預設為 true)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_skip_leading_gap(enabled: bool) -> bool {
+    SKIP_LEADING_GAP.store(enabled, Ordering::SeqCst);
+    SKIP_LEADING_GAP.load(Ordering::SeqCst)
+}
+
+/// 設定只在指定的應用程式位於前景時錄製 (焦點離開時暫停並發送 "RecordPaused"，回來時繼續並發送 "RecordResumed")
+/// - 無法取得前景應用程式的平台不會暫停 (支援的平台見 `focus` 模組)
+/// # 參數
+/// - `app` - Option<String> 應用程式名稱 (None 表示不限制，下次開始錄製時生效)
+/// # 返回值
+/// - Option<String> - 返回當前設定
+pub fn update_record_only_app(app: Option<String>) -> Option<String> {
+    let mut only_app = lock_or_recover(&RECORD_ONLY_APP);
+    *only_app = app.filter(|app| !app.trim().is_empty());
+    only_app.clone()
+}
+
+/// 設定錄製時是否記錄前景應用程式的變化 (以註記保存，回放時不會送出；支援的平台見 `focus` 模組)
+/// # 參數
+/// - `enabled` - bool 是否開啟 (預設為 false，下次開始錄製時生效)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_record_focus_changes(enabled: bool) -> bool {
+    RECORD_FOCUS_CHANGES.store(enabled, Ordering::SeqCst);
+    RECORD_FOCUS_CHANGES.load(Ordering::SeqCst)
+}
+
+/// 設定回放時是否使用高精度計時 (sleep 之後以忙碌等待補足最後不到 1 毫秒的時間，會多佔用一些 CPU)
+/// # 參數
+/// - `enabled` - bool 是否開啟 (預設為 false)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_high_precision_timing(enabled: bool) -> bool {
+    HIGH_PRECISION_TIMING.store(enabled, Ordering::SeqCst);
+    HIGH_PRECISION_TIMING.load(Ordering::SeqCst)
+}
+
+/// 設定回放時是否以系統的文字輸入一次送出連續的文字按鍵 (沒有按住修飾鍵、錄製時有產生文字的按鍵；其它按鍵仍逐鍵模擬)
+/// - 支援 macOS 與 Linux (X11)，其它平台與測試模式會維持逐鍵模擬 (見 `text_input` 模組)
+/// - 送出錄製時系統產生的文字 (依當時的鍵盤配置與 Caps Lock)，舊的錄製檔案沒有文字，會逐鍵模擬
+/// # 參數
+/// - `enabled` - bool 是否開啟 (預設為 false)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_unicode_injection(enabled: bool) -> bool {
+    UNICODE_INJECTION.store(enabled, Ordering::SeqCst);
+    UNICODE_INJECTION.load(Ordering::SeqCst)
+}
+
+/// 設定是否保留雙擊 (錄製時把雙擊的第二次點擊標記起來，回放時縮短它與第一次點擊之間的間隔)
+/// - rdev 無法取得系統的雙擊設定，以 500ms 內、移動不超過 4 像素的同一個按鍵視為雙擊
+/// # 參數
+/// - `enabled` - bool 是否保留 (預設為 true)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_preserve_doubleclicks(enabled: bool) -> bool {
+    PRESERVE_DOUBLECLICKS.store(enabled, Ordering::SeqCst);
+    PRESERVE_DOUBLECLICKS.load(Ordering::SeqCst)
+}
+
+/// 設定是否錄製媒體鍵 (音量、播放控制；以原始按鍵碼記錄，回放時送出相同的按鍵碼，支援的平台見 `media` 模組)
+/// # 參數
+/// - `enabled` - bool 是否錄製 (預設為 true)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_capture_media_keys(enabled: bool) -> bool {
+    CAPTURE_MEDIA_KEYS.store(enabled, Ordering::SeqCst);
+    CAPTURE_MEDIA_KEYS.load(Ordering::SeqCst)
+}
+
+/// 設定回放時在兩次滑鼠移動之間插入的中間移動數量 (讓稀疏的滑鼠移動看起來平順，原本的等待時間會平均分配)
+/// # 參數
+/// - `steps` - u32 中間移動的數量 (預設為 0，表示不插補)
+/// # 返回值
+/// - u32 - 返回當前設定
+pub fn update_mouse_interpolation(steps: u32) -> u32 {
+    MOUSE_INTERPOLATION_STEPS.store(steps, Ordering::SeqCst);
+    MOUSE_INTERPOLATION_STEPS.load(Ordering::SeqCst)
+}
+
+/// 設定錄製滑鼠座標的方式 (下次開始錄製時生效；回放時依錄製內容記錄的方式換算)
+/// - "pixels"：螢幕像素 (預設)
+/// - "normalized"：相對於主螢幕大小的比例，換了解析度也能回放到相同的相對位置
+/// - rdev 只能取得主螢幕的大小，多螢幕時其它螢幕上的位置也以主螢幕的大小換算 (會超出 0.0 - 1.0)，螢幕排列改變後就不準確
+/// - 開始錄製時無法取得螢幕大小會改以像素錄製
+/// # 參數
+/// - `mode` - &str "pixels" 或 "normalized"
+/// # 返回值
+/// - Result<String, String> - 返回當前設定，未知的方式時返回錯誤
+pub fn update_mouse_coordinate_mode(mode: &str) -> Result<String, String> {
+
+    let is_normalized = match mode.to_ascii_lowercase().as_str() {
+        "pixels" => false,
+        "normalized" => true,
+        _ => return Err(format!("未知的座標方式: {}", mode)),
+    };
+
+    NORMALIZED_MOUSE_COORDINATES.store(is_normalized, Ordering::SeqCst);
+    Ok(if NORMALIZED_MOUSE_COORDINATES.load(Ordering::SeqCst) { "normalized" } else { "pixels" }.to_string())
+}
+
+/// 設定回放執行緒的優先權 (回放時較不容易被其它程式搶走執行時間，時間較穩定)
+/// - "normal"：不調整 (預設)
+/// - "high"：回放期間提高執行回放的執行緒的優先權，回放結束後還原；沒有權限時 (例如 Linux 的一般使用者) 只記錄錯誤，以原本的優先權回放
+/// # 參數
+/// - `priority` - &str "normal" 或 "high"
+/// # 返回值
+/// - Result<String, String> - 返回當前設定，未知的優先權時返回錯誤
+pub fn update_playback_priority(priority: &str) -> Result<String, String> {
+
+    let is_high = match priority.to_ascii_lowercase().as_str() {
+        "normal" => false,
+        "high" => true,
+        _ => return Err(format!("未知的優先權: {}", priority)),
+    };
+
+    PLAYBACK_HIGH_PRIORITY.store(is_high, Ordering::SeqCst);
+    Ok(if PLAYBACK_HIGH_PRIORITY.load(Ordering::SeqCst) { "high" } else { "normal" }.to_string())
+}
+
+/// 設定隱私檢查的判斷條件
+/// # 參數
+/// - `settings` - PrivacyScanSettings
+/// # 返回值
+/// - PrivacyScanSettings - 返回當前設定
+pub fn update_privacy_scan_settings(settings: PrivacyScanSettings) -> PrivacyScanSettings {
+    let mut current = lock_or_recover(&PRIVACY_SCAN_SETTINGS);
+    *current = settings;
+    current.clone()
+}
+
+/// 設定回放時套用在滑鼠座標上的縮放與位移 (在安全邊界之前套用；以比例記錄的座標先換回像素再換算)
+/// # 參數
+/// - `transform` - Option<CoordTransform> None 表示不換算
+/// # 返回值
+/// - Result<Option<CoordTransform>, String> - 返回當前設定，數值不是有限值或縮放不大於 0 時返回錯誤
+pub fn update_mouse_transform(transform: Option<CoordTransform>) -> Result<Option<CoordTransform>, String> {
+
+    if let Some(transform) = &transform { transform.validate()?; }
+
+    let mut current = lock_or_recover(&MOUSE_TRANSFORM);
+    *current = transform;
+    Ok(*current)
+}
+
+/// 設定回放時滑鼠與螢幕邊緣保持的距離 (避免觸發系統的熱點角落；螢幕大小在每次回放開始時取得)
+/// - 只以主螢幕的範圍計算，多螢幕時移到其它螢幕的座標也會被限制在主螢幕內，使用多螢幕的錄製內容請維持 0
+/// # 參數
+/// - `px` - u64 像素 (預設為 0，表示不限制)
+/// # 返回值
+/// - u64 - 返回當前設定
+pub fn update_mouse_safe_margin(px: u64) -> u64 {
+    MOUSE_SAFE_MARGIN.store(px, Ordering::SeqCst);
+    MOUSE_SAFE_MARGIN.load(Ordering::SeqCst)
+}
+
+/// 設定回放時修飾鍵最少要按住多久才送出組合中的按鍵 (不足時延長該按鍵之前的等待)
+/// # 參數
+/// - `ms` - u64 毫秒 (預設為 0，表示不限制)
+/// # 返回值
+/// - u64 - 返回當前設定
+pub fn update_min_modifier_hold_ms(ms: u64) -> u64 {
+    MIN_MODIFIER_HOLD_MS.store(ms, Ordering::SeqCst);
+    MIN_MODIFIER_HOLD_MS.load(Ordering::SeqCst)
+}
+
+/// 設定回放時修飾鍵按下後額外等待的時間
+/// # 參數
+/// - `ms` - u64 毫秒 (預設為 5，0 表示不等待)
+/// # 返回值
+/// - u64 - 返回當前設定
+pub fn update_modifier_settle_ms(ms: u64) -> u64 {
+    MODIFIER_SETTLE_MS.store(ms, Ordering::SeqCst);
+    MODIFIER_SETTLE_MS.load(Ordering::SeqCst)
+}
+
+/// 設定每次回放最長的執行時間 (超過時停止回放並發送 "runtime-limit" 事件，避免回放不小心執行太久)
+/// - 以每一次開始的回放計算：播放清單的所有檔案、分支選擇的暫存內容共用同一段時間
+/// - 只停止超過時間的回放，同時進行的其他回放不受影響
+/// # 參數
+/// - `ms` - u64 最長執行時間 (毫秒，0 表示不限制)
+/// # 返回值
+/// - u64 - 返回當前設定
+pub fn update_max_playback_runtime_ms(ms: u64) -> u64 {
+    MAX_PLAYBACK_RUNTIME_MS.store(ms, Ordering::SeqCst);
+    MAX_PLAYBACK_RUNTIME_MS.load(Ordering::SeqCst)
+}
+
+/// 設定回放時是否降低 CPU 使用 (忙碌等待改為讓出執行緒，連續沒有間隔的事件之間會短暫休息，時間會稍微不準)
+/// # 參數
+/// - `enabled` - bool 是否開啟 (預設為 false)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_playback_cpu_friendly(enabled: bool) -> bool {
+    PLAYBACK_CPU_FRIENDLY.store(enabled, Ordering::SeqCst);
+    PLAYBACK_CPU_FRIENDLY.load(Ordering::SeqCst)
+}
+
+/// 設定回放時略過滑鼠移動的距離門檻 (與上一次送出的位置距離小於門檻時不送出)
+/// # 參數
+/// - `px` - f64 門檻 (像素，0 表示全部送出，預設為 0)
+/// # 返回值
+/// - f64 - 返回當前設定
+pub fn update_playback_move_threshold(px: f64) -> f64 {
+    PLAYBACK_MOVE_THRESHOLD_BITS.store(px.max(0.0).to_bits(), Ordering::SeqCst);
+    f64::from_bits(PLAYBACK_MOVE_THRESHOLD_BITS.load(Ordering::SeqCst))
+}
+
+/// 設定回放時是否略過停用事件 (以及只回放部分種類時被略過的事件) 的等待時間
+/// # 參數
+/// - `enabled` - bool 是否略過 (預設為 false，保留原本的等待時間)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_collapse_disabled_gaps(enabled: bool) -> bool {
+    COLLAPSE_DISABLED_GAPS.store(enabled, Ordering::SeqCst);
+    COLLAPSE_DISABLED_GAPS.load(Ordering::SeqCst)
+}
+
+/// 設定錄製中按下 Escape 時是否放棄錄製 (停止錄製並清除內容，Escape 本身不會被記錄)
+/// # 參數
+/// - `enabled` - bool 是否開啟 (預設為 false)
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_escape_aborts(enabled: bool) -> bool {
+    ESCAPE_ABORTS.store(enabled, Ordering::SeqCst);
+    ESCAPE_ABORTS.load(Ordering::SeqCst)
+}
+
+/// 設定錄製時不記錄的按鍵 / 按鍵組合 (取代先前的設定)
+/// # 參數
+/// - `combos` - &[String] 按鍵組合 (例如 `META+KeyQ` 只擋 Command+Q，`KeyQ` 則擋所有的 Q)
+/// # 返回值
+/// - Result<usize, String> - 設定的組合數量，有無法辨識的組合時返回錯誤且不變更設定
+pub fn update_key_blocklist(combos: &[String]) -> Result<usize, String> {
+
+    let blocklist = combos.iter().map(|combo| parse_combo(combo)).collect::<Result<Vec<KeyCombo>, String>>()?;
+    let count = blocklist.len();
+    *lock_or_recover(&KEY_BLOCKLIST) = blocklist;
+
+    Ok(count)
+}
+
+/// 設定掃描時視為危險的按鍵組合 (取代先前的設定)
+/// # 參數
+/// - `combos` - &[String] 按鍵組合 (例如 `META+KeyQ`、`CTRL+ALT+Delete`)
+/// # 返回值
+/// - Result<usize, String> - 設定的組合數量，有無法辨識的組合時返回錯誤且不變更設定
+pub fn update_danger_chords(combos: &[String]) -> Result<usize, String> {
+
+    let chords = combos.iter().map(|combo| parse_combo(combo)).collect::<Result<Vec<KeyCombo>, String>>()?;
+    let count = chords.len();
+    *lock_or_recover(&DANGER_CHORDS) = chords;
+
+    Ok(count)
+}
+
+/// 設定回放時平滑事件間隔的範圍 (以犧牲精確的時間換取較順暢的回放，總時間不變)
+/// # 參數
+/// - `window` - usize 移動平均的事件數量 (0 表示關閉，預設為 0)
+/// # 返回值
+/// - usize - 返回當前設定
+pub fn update_timing_smoothing(window: usize) -> usize {
+    TIMING_SMOOTHING_WINDOW.store(window, Ordering::SeqCst);
+    TIMING_SMOOTHING_WINDOW.load(Ordering::SeqCst)
+}
+
+/// 設定模擬事件失敗時的重試次數
+/// # 參數
+/// - `retries` - usize 重試次數 (0 表示不重試)
+/// # 返回值
+/// - usize - 返回當前設定
+pub fn update_simulate_retries(retries: usize) -> usize {
+    SIMULATE_RETRIES.store(retries, Ordering::SeqCst);
+    SIMULATE_RETRIES.load(Ordering::SeqCst)
+}
+
+/// 取得模擬事件累計的重試次數
+/// # 返回值
+/// - u64
+pub fn simulate_retries_used() -> u64 {
+    SIMULATE_RETRY_COUNT.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::clock::MockClock;
+    use super::*;
+
+    /// 依監聽執行緒的流程處理一個事件 (rdev 的 Event 無法在測試中建立，只傳入時間與事件類型)
+    fn feed(handle: &RecorderHandle, time: SystemTime, event_type: EventType) {
+        if is_key_recorded(event_type) { store_into(&handle.inner, time, event_type, None); }
+    }
+
+    fn event_types(events: &[StoredEvent]) -> Vec<EventType> { events.iter().map(|event| event.event_type).collect() }
+
+    #[test]
+    fn lone_modifier_tap_is_recorded_and_replayed() {
+
+        let _serial = crate::serial_test();
+        update_key_blocklist(&["CTRL+KeyC".to_string()]).unwrap();
+
+        let handle = RecorderBuilder::default().build();
+        handle.start();
+        let started_at = handle.lock().started_at.unwrap();
+        feed(&handle, started_at + Duration::from_millis(10), KeyPress(Key::ControlLeft));
+        feed(&handle, started_at + Duration::from_millis(40), KeyRelease(Key::ControlLeft));
+        handle.stop(false);
+        update_key_blocklist(&[]).unwrap();
+
+        let tap = [KeyPress(Key::ControlLeft), KeyRelease(Key::ControlLeft)];
+        let (events, started_at) = handle.recorded_events();
+        assert_eq!(event_types(&events), tap);
+
+        let (was_coalesced, threshold_ms) = crate::storage::coalesce_taps();
+        crate::storage::update_coalesce_taps(true, 200);
+        let bytes = crate::storage::encode_record(&events, started_at, &RecordInfo::default());
+        crate::storage::update_coalesce_taps(was_coalesced, threshold_ms);
+        let (loaded, _, _) = crate::storage::decode_record(&bytes.unwrap(), UNIX_EPOCH).unwrap();
+        assert_eq!(event_types(&loaded), tap);
+
+        update_test_mode(true);
+        let outcome = handle.play();
+        let output = test_mode_output();
+        update_test_mode(false);
+
+        assert_eq!(outcome, PlaybackOutcome::Completed);
+        assert_eq!(output, tap.iter().map(|event_type| format!("{:?}", event_type)).collect::<Vec<_>>());
+    }
+
+    fn without_zero(durations: Vec<Duration>) -> Vec<Duration> { durations.into_iter().filter(|duration| !duration.is_zero()).collect() }
+
+    #[test]
+    fn mock_clock_drives_recording_and_playback() {
+
+        let _serial = crate::serial_test();
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000));
+        let handle = RecorderBuilder::default().clock(clock.clone()).build();
+
+        let count = handle.build_from_keys(&["KeyA".to_string(), "KeyB".to_string()], 10).unwrap();
+        let (events, started_at) = handle.recorded_events();
+        assert_eq!(count, 4);
+        assert_eq!(started_at, Some(clock.now()));
+        assert_eq!(events.iter().map(|event| event.time).collect::<Vec<_>>(), (1..=4).map(|step| clock.now() + Duration::from_millis(10 * step)).collect::<Vec<_>>());
+
+        update_test_mode(true);
+        let outcome = handle.play();
+        update_test_mode(false);
+
+        // 跳過開頭空白 (預設)，之後每個間隔都透過時鐘等待，不會真的 sleep
+        assert_eq!(outcome, PlaybackOutcome::Completed);
+        assert_eq!(without_zero(clock.sleeps()), vec![Duration::from_millis(10); 3]);
+    }
+
+    #[test]
+    fn clock_jump_during_recording_follows_elapsed() {
+
+        let _serial = crate::serial_test();
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(10_000));
+        let handle = RecorderBuilder::default().clock(clock.clone()).build();
+        let step = |ms: u64, event_type: EventType| { clock.advance(Duration::from_millis(ms)); feed(&handle, clock.now(), event_type); };
+
+        handle.start();
+        step(10, KeyPress(Key::KeyA));
+        step(20, KeyRelease(Key::KeyA));
+        clock.set_now(clock.now() - Duration::from_secs(3_600));
+        step(30, KeyPress(Key::KeyB));
+        clock.set_now(clock.now() + Duration::from_secs(7_200));
+        step(40, KeyRelease(Key::KeyB));
+        handle.stop(false);
+
+        let (events, started_at) = handle.recorded_events();
+        assert_eq!(events.iter().map(|event| offset_ms_of(event, started_at)).collect::<Vec<_>>(), vec![10, 30, 60, 100]);
+
+        // 範圍依單調時間切出 (系統時間已經往回 / 往前調整)
+        let path = std::env::temp_dir().join(format!("slice-{}.tkr", new_session_id()));
+        let path = path.to_str().unwrap();
+        let sliced = handle.save_time_slice_to(25, 65, &FileStorage::new(path));
+        let loaded = crate::storage::read_record_file(path);
+        let _ = std::fs::remove_file(path);
+        assert_eq!(sliced, Ok(2));
+        let (slice, slice_start, _) = loaded.unwrap();
+        assert_eq!(event_types(&slice), vec![KeyRelease(Key::KeyA), KeyPress(Key::KeyB)]);
+        assert_eq!(slice.iter().map(|event| offset_ms_of(event, Some(slice_start))).collect::<Vec<_>>(), vec![5, 35]);
+
+        update_test_mode(true);
+        let played = clock.sleeps().len();
+        assert!(handle.play_window(25, 65));
+        let window_output = test_mode_output();
+        let window_sleeps = without_zero(clock.sleeps()[played..].to_vec());
+
+        let played = clock.sleeps().len();
+        let outcome = handle.play();
+        let output = test_mode_output();
+        let sleeps = without_zero(clock.sleeps()[played..].to_vec());
+        update_test_mode(false);
+
+        assert_eq!(window_output, vec![format!("{:?}", KeyRelease(Key::KeyA)), format!("{:?}", KeyPress(Key::KeyB))]);
+        assert_eq!(window_sleeps, vec![Duration::from_millis(30)]);
+
+        // 回放的間隔依單調時間，不會因為系統時間往回調整而變成 0，或往前調整而等待兩小時
+        assert_eq!(outcome, PlaybackOutcome::Completed);
+        assert_eq!(output.len(), window_output.len() + 4);
+        assert_eq!(sleeps, vec![Duration::from_millis(20), Duration::from_millis(30), Duration::from_millis(40)]);
+    }
+
+    #[test]
+    fn right_side_modifiers_survive_save_and_playback() {
+
+        let _serial = crate::serial_test();
+        let keys = [
+            KeyPress(Key::ControlRight),
+            KeyPress(Key::ShiftRight),
+            KeyPress(Key::KeyA),
+            KeyRelease(Key::KeyA),
+            KeyRelease(Key::ShiftRight),
+            KeyRelease(Key::ControlRight),
+            KeyPress(Key::AltGr),
+            KeyPress(Key::KeyQ),
+            KeyRelease(Key::KeyQ),
+            KeyRelease(Key::AltGr),
+            KeyPress(Key::ShiftRight),
+            KeyRelease(Key::ShiftRight),
+        ];
+
+        let recorded = RecorderBuilder::default().build();
+        recorded.start();
+        let started_at = recorded.lock().started_at.unwrap();
+        for (index, event_type) in keys.iter().enumerate() { feed(&recorded, started_at + Duration::from_millis(10 * (index as u64 + 1)), *event_type); }
+        recorded.stop(false);
+        assert_eq!(event_types(&recorded.recorded_events().0), keys);
+
+        let (was_coalesced, threshold_ms) = crate::storage::coalesce_taps();
+        for is_coalesced in [false, true] {
+
+            let path = std::env::temp_dir().join(format!("modifiers-{}.tkr", new_session_id()));
+            let path = path.to_str().unwrap();
+            let storage = FileStorage::new(path);
+            crate::storage::update_coalesce_taps(is_coalesced, 200);
+            let saved = recorded.save_to(&storage);
+            let loaded = RecorderBuilder::default().build();
+            let count = loaded.load_from(&storage);
+            let _ = std::fs::remove_file(path);
+            crate::storage::update_coalesce_taps(was_coalesced, threshold_ms);
+
+            assert_eq!(saved, Ok(()));
+            assert_eq!(count, Ok(keys.len()));
+            assert_eq!(event_types(&loaded.recorded_events().0), keys);
+
+            update_test_mode(true);
+            let outcome = loaded.play();
+            let output = test_mode_output();
+            update_test_mode(false);
+
+            assert_eq!(outcome, PlaybackOutcome::Completed);
+            assert_eq!(output, keys.iter().map(|event_type| format!("{:?}", event_type)).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn rollover_keeps_recorded_order() {
+
+        let _serial = crate::serial_test();
+        let keys = [Key::KeyW, Key::KeyA, Key::KeyS, Key::KeyD, Key::ShiftLeft, Key::Space, Key::KeyE, Key::KeyQ];
+
+        // 八個按鍵依序按下 (重疊按住)，放開的順序與按下不同，中間再按下與放開其中幾個
+        let mut input: Vec<EventType> = keys.iter().map(|key| KeyPress(*key)).collect();
+        input.extend([KeyRelease(Key::KeyS), KeyRelease(Key::KeyW), KeyPress(Key::KeyS), KeyRelease(Key::Space), KeyRelease(Key::KeyQ)]);
+        input.extend([Key::KeyA, Key::KeyE, Key::ShiftLeft, Key::KeyD, Key::KeyS].iter().map(|key| KeyRelease(*key)));
+
+        let handle = RecorderBuilder::default().build();
+        handle.start();
+        let started_at = handle.lock().started_at.unwrap();
+        for (index, event_type) in input.iter().enumerate() { feed(&handle, started_at + Duration::from_millis(index as u64), *event_type); }
+        handle.stop(false);
+
+        let (events, _) = handle.recorded_events();
+        assert_eq!(event_types(&events), input);
+        assert!(lock_or_recover(&HELD_KEYS).is_empty());
+
+        update_test_mode(true);
+        let outcome = handle.play();
+        let output = test_mode_output();
+        update_test_mode(false);
+
+        assert_eq!(outcome, PlaybackOutcome::Completed);
+        assert_eq!(output, input.iter().map(|event_type| format!("{:?}", event_type)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn undo_restores_info_and_start_time() {
+
+        let handle = RecorderBuilder::default().build();
+        let started_at = UNIX_EPOCH + Duration::from_secs(100);
+        {
+            let mut recorder = handle.lock();
+            recorder.events = vec![StoredEvent::new(started_at + Duration::from_millis(5), KeyPress(Key::KeyA))];
+            recorder.started_at = Some(started_at);
+            recorder.info.name = "原本".to_string();
+        }
+
+        let path = std::env::temp_dir().join(format!("undo-{}.tkr", new_session_id()));
+        let path = path.to_str().unwrap();
+        let loaded_info = RecordInfo { name: "載入".to_string(), tags: vec!["tag".to_string()], ..RecordInfo::default() };
+        crate::storage::write_record_file(path, &[StoredEvent::new(UNIX_EPOCH, KeyPress(Key::KeyB))], Some(UNIX_EPOCH), &loaded_info).unwrap();
+        let loaded = handle.load_from(&FileStorage::new(path));
+        let _ = std::fs::remove_file(path);
+        assert_eq!(loaded, Ok(1));
+        let loaded_started_at = handle.lock().started_at;
+
+        assert!(handle.undo());
+        let (events, undone_started_at) = handle.recorded_events();
+        assert_eq!(event_types(&events), vec![KeyPress(Key::KeyA)]);
+        assert_eq!(undone_started_at, Some(started_at));
+        assert_eq!(handle.record_info().name, "原本");
+
+        assert!(handle.redo());
+        let (events, redone_started_at) = handle.recorded_events();
+        assert_eq!(event_types(&events), vec![KeyPress(Key::KeyB)]);
+        assert_eq!(redone_started_at, loaded_started_at);
+        assert_eq!(handle.record_info(), loaded_info);
+    }
+
+    #[test]
+    fn remaining_time_follows_playback_delays() {
+
+        let _serial = crate::serial_test();
+
+        // 系統時間的間隔 (500 ms) 與單調時間的間隔不同，估算應與回放迴圈相同以單調時間與固定間隔計算
+        let at = |wall_ms: u64, elapsed_ms: u64, event_type: EventType| StoredEvent { elapsed: Some(Duration::from_millis(elapsed_ms)), ..StoredEvent::new(UNIX_EPOCH + Duration::from_millis(wall_ms), event_type) };
+        let events = vec![
+            at(10, 10, KeyPress(Key::KeyA)),
+            at(500, 30, KeyRelease(Key::KeyA)),
+            at(1000, 60, MouseMove { x: 1.0, y: 1.0 }),
+            at(1500, 100, KeyPress(Key::KeyB)),
+        ];
+        let options = PlaybackOptions { fixed_key_gap: Some(Duration::from_millis(5)), ..Default::default() };
+
+        update_test_mode(true);
+        let (id, playback) = register_playback(events, Some(UNIX_EPOCH), Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)), Arc::new(SystemClock), options, Vec::new());
+        playback.index.store(1, Ordering::SeqCst);
+        let remaining = playback_remaining_ms();
+        unregister_playback(id);
+        update_test_mode(false);
+
+        assert_eq!(remaining, Some(5 + 30 + 5));
+    }
+
+    #[test]
+    fn diff_aligns_mouse_moves_and_reports_drift() {
+
+        let at = |ms: u64, event_type: EventType| StoredEvent::new(UNIX_EPOCH + Duration::from_millis(ms), event_type);
+        let baseline = [at(0, MouseMove { x: 10.0, y: 10.0 }), at(10, KeyPress(Key::KeyA)), at(20, MouseMove { x: 50.0, y: 50.0 }), at(30, KeyRelease(Key::KeyA))];
+        let current = [at(0, MouseMove { x: 10.5, y: 9.5 }), at(10, KeyPress(Key::KeyA)), at(20, MouseMove { x: 80.0, y: 50.0 }), at(30, KeyRelease(Key::KeyA))];
+
+        let entries = diff_events(&current, &baseline, None, DIFF_POSITION_TOLERANCE).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].kind, entries[0].index, entries[0].baseline_index), (DiffKind::Changed, Some(2), Some(2)));
+
+        let inserted = [at(0, MouseMove { x: 10.0, y: 10.0 }), at(5, KeyPress(Key::KeyB)), at(10, KeyPress(Key::KeyA)), at(20, MouseMove { x: 50.0, y: 50.0 }), at(30, KeyRelease(Key::KeyA))];
+        let entries = diff_events(&inserted, &baseline, None, DIFF_POSITION_TOLERANCE).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].kind, entries[0].index), (DiffKind::Added, Some(1)));
+    }
+
+    #[test]
+    fn diff_refuses_inputs_above_the_cell_cap() {
+
+        let side = 5_000;
+        assert!(side * side > MAX_DIFF_CELLS);
+
+        let current = vec![StoredEvent::new(UNIX_EPOCH, KeyPress(Key::KeyA)); side];
+        let baseline = vec![StoredEvent::new(UNIX_EPOCH, KeyPress(Key::KeyB)); side];
+        assert!(diff_events(&current, &baseline, None, DIFF_POSITION_TOLERANCE).is_err());
+
+        // 相同的開頭與結尾不需要對齊表格
+        let same = vec![StoredEvent::new(UNIX_EPOCH, KeyPress(Key::KeyA)); side * 2];
+        assert_eq!(diff_events(&same, &same, None, DIFF_POSITION_TOLERANCE).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn undo_reverts_record_info() {
+
+        let handle = RecorderBuilder::default().build();
+        handle.set_record_info("first".to_string(), String::new());
+        handle.set_record_info("second".to_string(), String::new());
+
+        assert!(handle.undo());
+        assert_eq!(handle.record_info().name, "first");
+    }
+
+    #[test]
+    fn slot_call_edits_are_undoable_and_cut_with_the_events() {
+
+        let _serial = crate::serial_test();
+        let name = "test-slot-call-edits";
+
+        let handle = RecorderBuilder::default().build();
+        handle.build_from_keys(&["A".to_string(), "B".to_string(), "C".to_string()], 10).unwrap();
+        handle.store_slot(name);
+
+        let count = handle.events().len();
+        handle.add_slot_call(1, name).unwrap();
+        handle.add_slot_call(count, name).unwrap();
+        assert!(handle.undo());
+        assert_eq!(handle.record_info().calls.len(), 1);
+        handle.add_slot_call(count, name).unwrap();
+
+        handle.split(2).unwrap();
+        let calls = handle.record_info().calls;
+        lock_or_recover(&SLOTS).remove(name);
+
+        assert_eq!(calls.iter().map(|call| call.index).collect::<Vec<_>>(), [1]);
+        assert!(handle.undo());
+        assert_eq!(handle.record_info().calls.len(), 2);
+    }
+
+    #[test]
+    fn only_the_oldest_synthetic_event_is_matched() {
+
+        let _serial = crate::serial_test();
+        lock_or_recover(&SYNTHETIC_EVENTS).clear();
+
+        mark_synthetic(&KeyPress(Key::KeyA));
+        mark_synthetic(&KeyPress(Key::KeyB));
+
+        assert!(!take_synthetic(&KeyPress(Key::KeyB)));
+        assert!(take_synthetic(&KeyPress(Key::KeyA)));
+        assert!(take_synthetic(&KeyPress(Key::KeyB)));
+
+        mark_synthetic(&KeyPress(Key::KeyC));
+        unmark_synthetic(&[KeyPress(Key::KeyC)]);
+        assert!(!take_synthetic(&KeyPress(Key::KeyC)));
+    }
+
+    #[test]
+    fn modifier_hold_is_measured_on_the_playback_clock() {
+
+        let _serial = crate::serial_test();
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(1_000));
+        let handle = RecorderBuilder::default().clock(clock.clone()).build();
+
+        let started_at = clock.now();
+        let at = |ms: u64, event_type: EventType| StoredEvent::new(started_at + Duration::from_millis(ms), event_type);
+        let mut recorder = handle.lock();
+        recorder.events = vec![at(0, KeyPress(Key::ControlLeft)), at(10, KeyPress(Key::KeyC)), at(20, KeyRelease(Key::KeyC)), at(30, KeyRelease(Key::ControlLeft))];
+        recorder.started_at = Some(started_at);
+        drop(recorder);
+
+        let settle_ms = MODIFIER_SETTLE_MS.load(Ordering::SeqCst);
+        update_modifier_settle_ms(0);
+        update_min_modifier_hold_ms(50);
+        update_test_mode(true);
+        let outcome = handle.play();
+        update_test_mode(false);
+        update_min_modifier_hold_ms(0);
+        update_modifier_settle_ms(settle_ms);
+
+        // Ctrl 按下 10 毫秒後就要送出 C，再多等 40 毫秒補足按住的時間
+        assert_eq!(outcome, PlaybackOutcome::Completed);
+        assert_eq!(without_zero(clock.sleeps()), [10, 40, 10, 10].map(Duration::from_millis));
+    }
+
+    #[test]
+    fn branch_edits_are_undoable_and_cut_with_the_events() {
+
+        let _serial = crate::serial_test();
+        let name = "test-branch-edits";
+
+        let handle = RecorderBuilder::default().build();
+        handle.build_from_keys(&["A".to_string(), "B".to_string()], 10).unwrap();
+        handle.store_slot(name);
+        handle.set_record_info("branches".to_string(), String::new());
+
+        handle.add_branch(4, Key::ShiftLeft, Some(name.to_string()), None).unwrap();
+        assert!(handle.undo());
+        assert!(handle.record_info().branches.is_empty());
+        assert_eq!(handle.record_info().name, "branches");
+
+        handle.add_branch(1, Key::ShiftLeft, Some(name.to_string()), None).unwrap();
+        handle.add_branch(4, Key::ShiftLeft, None, Some(name.to_string())).unwrap();
+        assert_eq!(handle.trim_after_last_key(), 0);
+        handle.split(2).unwrap();
+        lock_or_recover(&SLOTS).remove(name);
+
+        assert_eq!(handle.record_info().branches.iter().map(|branch| branch.index).collect::<Vec<_>>(), [1]);
+        assert!(handle.undo());
+        assert_eq!(handle.record_info().branches.len(), 2);
+    }
+
+    #[test]
+    fn repair_timing_orders_by_elapsed_and_falls_back_to_time() {
+
+        let _serial = crate::serial_test();
+        let clock = MockClock::new(UNIX_EPOCH + Duration::from_secs(10_000));
+        let handle = RecorderBuilder::default().clock(clock.clone()).build();
+        let step = |ms: u64, event_type: EventType| { clock.advance(Duration::from_millis(ms)); feed(&handle, clock.now(), event_type); };
+
+        handle.start();
+        step(10, KeyPress(Key::KeyA));
+        step(20, KeyRelease(Key::KeyA));
+        clock.set_now(clock.now() - Duration::from_secs(3_600));
+        step(30, KeyPress(Key::KeyB));
+        step(40, KeyRelease(Key::KeyB));
+        handle.stop(false);
+
+        // 系統時間往回調整過，但單調時間的順序正確，不需要修復
+        assert_eq!(handle.repair_timing(), 0);
+        assert_eq!(event_types(&handle.recorded_events().0), vec![KeyPress(Key::KeyA), KeyRelease(Key::KeyA), KeyPress(Key::KeyB), KeyRelease(Key::KeyB)]);
+
+        // 沒有單調時間的事件依系統時間排序
+        for event in handle.lock().events.iter_mut() { event.elapsed = None; }
+        assert_eq!(handle.repair_timing(), 4);
+        assert_eq!(event_types(&handle.recorded_events().0), vec![KeyPress(Key::KeyB), KeyRelease(Key::KeyB), KeyPress(Key::KeyA), KeyRelease(Key::KeyA)]);
+    }
+
+    #[test]
+    fn redacted_keys_leave_no_trace_in_saved_or_exported_output() {
+
+        let _serial = crate::serial_test();
+        let secret = [Key::KeyQ, Key::KeyZ, Key::KeyX];
+
+        let handle = RecorderBuilder::default().build();
+        handle.build_from_keys(&["KeyA", "KeyQ", "KeyZ", "KeyX"].map(String::from), 10).unwrap();
+        handle.lock().events[2].text = Some("q".to_string());
+        assert_eq!(handle.redact_range(25, 80), Ok(6));
+
+        let (events, started_at) = handle.recorded_events();
+        let is_secret = |event: &StoredEvent| matches!(event.event_type, KeyPress(key) | KeyRelease(key) if secret.contains(&key));
+
+        let bytes = crate::storage::encode_record(&events, started_at, &handle.record_info()).unwrap();
+        let (loaded, _, _) = crate::storage::decode_record(&bytes, UNIX_EPOCH).unwrap();
+        assert_eq!(loaded.len(), 8);
+        assert!(!loaded.iter().any(is_secret));
+        assert!(loaded.iter().all(|event| event.text.is_none()));
+
+        let path = std::env::temp_dir().join(format!("redacted-{}.csv", new_session_id()));
+        let path = path.to_str().unwrap();
+        let exported = handle.export_csv(path);
+        let csv = std::fs::read_to_string(path);
+        let _ = std::fs::remove_file(path);
+        assert_eq!(exported, Ok(8));
+        let csv = csv.unwrap();
+        assert!(csv.contains("KeyA"));
+        assert!(secret.iter().all(|key| !csv.contains(&format!("{:?}", key))));
+
+        assert_eq!(handle.extract_text(), "a");
+    }
+
+    #[test]
+    fn runtime_limit_stops_only_the_playback_that_ran_over() {
+
+        let _serial = crate::serial_test();
+        let original = MAX_PLAYBACK_RUNTIME_MS.load(Ordering::SeqCst);
+        let recorder_stop = Arc::new(AtomicBool::new(false));
+        let (over, other) = (Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)));
+
+        update_max_playback_runtime_ms(0);
+        let is_unlimited_finished = start_runtime_watchdog(other.clone());
+
+        update_max_playback_runtime_ms(20);
+        let is_finished = start_runtime_watchdog(over.clone());
+        update_max_playback_runtime_ms(original);
+
+        let (id, playback) = register_playback(vec![StoredEvent::new(UNIX_EPOCH, KeyPress(Key::KeyA))], None, over.clone(), recorder_stop.clone(), Arc::new(SystemClock), PlaybackOptions::default(), Vec::new());
+        sleep(Duration::from_millis(200));
+        unregister_playback(id);
+        is_finished.store(true, Ordering::SeqCst);
+        is_unlimited_finished.store(true, Ordering::SeqCst);
+
+        assert!(playback.is_stopped());
+        assert!(!other.load(Ordering::SeqCst));
+        assert!(!recorder_stop.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,58 @@
+//! 螢幕截圖 (需啟用 `screen-capture` feature)
+//! - 多螢幕時只會擷取主螢幕
+
+use xcap::Monitor;
+use image::RgbaImage;
+use crate::recorder::DisplayBounds;
+
+/// 擷取主螢幕並存成 PNG
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<(), String>
+pub fn capture_primary_screen(path: &str) -> Result<(), String> {
+    let image = capture_primary_image()?;
+    image.save_with_format(path, image::ImageFormat::Png).map_err(|error| error.to_string())
+}
+
+/// 擷取主螢幕並與參考圖片比較
+/// # 參數
+/// - `reference_path` - &str 參考圖片的檔案路徑
+/// # 返回值
+/// - Result<f64, String> - 差異程度 (0.0 表示完全相同，1.0 表示完全不同；大小不同時為 1.0)
+pub fn compare_primary_screen(reference_path: &str) -> Result<f64, String> {
+
+    let reference = image::open(reference_path).map_err(|error| format!("無法讀取參考圖片: {}", error))?.to_rgba8();
+    let image = capture_primary_image()?;
+
+    if image.dimensions() != reference.dimensions() { return Ok(1.0); }
+
+    let total: u64 = image.pixels().zip(reference.pixels())
+        .flat_map(|(pixel, reference)| pixel.0.into_iter().zip(reference.0).take(3))
+        .map(|(value, reference)| value.abs_diff(reference) as u64)
+        .sum();
+
+    let channels = image.width() as u64 * image.height() as u64 * 3;
+    Ok(if channels == 0 { 0.0 } else { total as f64 / (channels * 255) as f64 })
+}
+
+/// 擷取主螢幕的畫面
+/// # 返回值
+/// - Result<RgbaImage, String>
+pub(crate) fn capture_primary_image() -> Result<RgbaImage, String> {
+
+    let monitors = Monitor::all().map_err(|error| error.to_string())?;
+    let monitor = monitors.iter().find(|monitor| monitor.is_primary()).or_else(|| monitors.first()).ok_or_else(|| "找不到螢幕".to_string())?;
+
+    monitor.capture_image().map_err(|error| error.to_string())
+}
+
+/// 取得所有螢幕在桌面上的位置與大小 (依系統返回的順序編號)
+/// - macOS 為點 (point)，Windows / Linux 為像素，與 rdev 的滑鼠座標相同
+/// # 返回值
+/// - Result<Vec<DisplayBounds>, String>
+pub fn display_bounds() -> Result<Vec<DisplayBounds>, String> {
+
+    let monitors = Monitor::all().map_err(|error| error.to_string())?;
+    Ok(monitors.iter().map(|monitor| DisplayBounds { x: monitor.x() as f64, y: monitor.y() as f64, width: monitor.width() as f64, height: monitor.height() as f64 }).collect())
+}
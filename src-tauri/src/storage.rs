@@ -0,0 +1,969 @@
+//! 錄製檔案的讀寫
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use schemars::JsonSchema;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{RootSchema, Schema};
+use rdev::{EventType, Key};
+
+use crate::recorder::StoredEvent;
+use crate::locks::LockState;
+
+pub const RECORD_FILE_VERSION: u32 = 4;
+
+/// 二進位錄製檔案的開頭標記 (版本 1 為 JSON，沒有此標記)
+const BINARY_MAGIC: &[u8; 4] = b"TKRB";
+
+const TAG_KEY_PRESS: u8 = 0;
+const TAG_KEY_RELEASE: u8 = 1;
+const TAG_BUTTON_PRESS: u8 = 2;
+const TAG_BUTTON_RELEASE: u8 = 3;
+const TAG_MOUSE_MOVE: u8 = 4;
+const TAG_WHEEL: u8 = 5;
+const TAG_KEY_TAP: u8 = 6; // 版本 3：快速按下再放開的同一個按鍵 (按鍵 + 放開的時間)
+
+static COALESCE_TAPS: AtomicBool = AtomicBool::new(false);
+static TAP_THRESHOLD_MS: AtomicU64 = AtomicU64::new(200);
+
+/// 錄製檔案的附加資訊 (二進位格式以 JSON 儲存，新欄位需提供預設值)
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+struct RecordMetadata {
+    disabled: Vec<usize>,
+    double_clicks: Vec<usize>,
+    labels: BTreeMap<usize, String>,
+    texts: BTreeMap<usize, String>,
+    tags: Vec<String>,
+    name: String,
+    description: String,
+    lock_state: Option<LockState>,
+    calls: Vec<SlotCall>,
+    branches: Vec<SlotBranch>,
+    session_id: Option<String>,
+    coordinate_mode: CoordinateMode,
+}
+
+/// 滑鼠座標的記錄方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CoordinateMode {
+    #[default]
+    Pixels,     // 螢幕像素
+    Normalized, // 相對於主螢幕大小的比例 (0.0 - 1.0)
+}
+
+/// 回放時插入另一個暫存錄製內容的位置 (被呼叫的內容會在該事件之前展開回放)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SlotCall {
+    pub index: usize,
+    pub name: String,
+}
+
+/// 回放時依按鍵是否按住選擇要插入的暫存錄製內容 (在該事件之前判斷；沒有指定名稱的分支不回放任何內容)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotBranch {
+    pub index: usize,
+    #[schemars(with = "KeySchema")]
+    pub key: Key,
+    pub then_slot: Option<String>, // 按住時回放
+    pub else_slot: Option<String>, // 沒有按住時回放
+}
+
+/// 錄製內容的附加資訊 (與事件一起儲存)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordInfo {
+    pub tags: Vec<String>,
+    pub name: String,
+    pub description: String,
+    pub lock_state: Option<LockState>,
+    pub calls: Vec<SlotCall>,
+    pub branches: Vec<SlotBranch>,
+    pub session_id: Option<String>,
+    pub coordinate_mode: CoordinateMode,
+}
+
+/// 版本 1 錄製檔案內的單一事件 (時間以距離錄製開始的毫秒數記錄)
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct RecordFileEvent {
+    offset_ms: u64,
+    #[schemars(with = "EventTypeSchema")]
+    event_type: EventType,
+}
+
+/// 版本 1 錄製檔案格式 (JSON)
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct RecordFile {
+    #[schemars(range(min = 1, max = 1))]
+    version: u32,
+    events: Vec<RecordFileEvent>,
+}
+
+/// 錄製內容的儲存方式 (可由使用本函式庫的程式自行實作)
+pub trait StorageBackend: Send + Sync {
+
+    /// 儲存錄製事件
+    /// # 參數
+    /// - `events` - &[StoredEvent] 錄製的事件
+    /// - `started_at` - Option<SystemTime> 錄製開始的時間
+    /// # 返回值
+    /// - Result<(), String>
+    fn save(&self, events: &[StoredEvent], started_at: Option<SystemTime>) -> Result<(), String>;
+
+    /// 讀取錄製事件
+    /// # 返回值
+    /// - Result<(Vec<StoredEvent>, SystemTime), String> - 錄製的事件與錄製開始的時間
+    fn load(&self) -> Result<(Vec<StoredEvent>, SystemTime), String>;
+
+    /// 儲存錄製事件與附加資訊 (預設忽略附加資訊)
+    /// # 參數
+    /// - `events` - &[StoredEvent] 錄製的事件
+    /// - `started_at` - Option<SystemTime> 錄製開始的時間
+    /// - `info` - &RecordInfo 附加資訊
+    /// # 返回值
+    /// - Result<(), String>
+    fn save_with_info(&self, events: &[StoredEvent], started_at: Option<SystemTime>, _info: &RecordInfo) -> Result<(), String> {
+        self.save(events, started_at)
+    }
+
+    /// 讀取錄製事件與附加資訊 (預設附加資訊為空)
+    /// # 返回值
+    /// - Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String>
+    fn load_with_info(&self) -> Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> {
+        let (events, started_at) = self.load()?;
+        Ok((events, started_at, RecordInfo::default()))
+    }
+}
+
+/// 以 JSON 檔案儲存錄製內容
+pub struct FileStorage {
+    path: String,
+}
+
+impl FileStorage {
+
+    /// 建立指定路徑的檔案儲存
+    /// # 參數
+    /// - `path` - &str 檔案路徑
+    pub fn new(path: &str) -> Self { FileStorage { path: path.to_string() } }
+}
+
+impl StorageBackend for FileStorage {
+
+    fn save(&self, events: &[StoredEvent], started_at: Option<SystemTime>) -> Result<(), String> {
+        write_record_file(&self.path, events, started_at, &RecordInfo::default())
+    }
+
+    fn load(&self) -> Result<(Vec<StoredEvent>, SystemTime), String> {
+        let (events, started_at, _) = read_record_file(&self.path)?;
+        Ok((events, started_at))
+    }
+
+    fn save_with_info(&self, events: &[StoredEvent], started_at: Option<SystemTime>, info: &RecordInfo) -> Result<(), String> {
+        write_record_file(&self.path, events, started_at, info)
+    }
+
+    fn load_with_info(&self) -> Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> {
+        read_record_file(&self.path)
+    }
+}
+
+/// 錄製時即時寫入的事件記錄檔 (每行一個 JSON 事件，只會附加在檔案最後；每次開始錄製時清空，只保留最近一次錄製)
+pub struct EventLog {
+    writer: LineWriter<File>,
+}
+
+impl EventLog {
+
+    /// 開啟記錄檔 (不存在時建立)
+    /// # 參數
+    /// - `path` - &str 檔案路徑
+    /// # 返回值
+    /// - Result<EventLog, String>
+    pub fn open(path: &str) -> Result<Self, String> {
+        let file = OpenOptions::new().create(true).append(true).open(path).map_err(|error| error.to_string())?;
+        Ok(EventLog { writer: LineWriter::new(file) })
+    }
+
+    /// 清空記錄檔 (開始新的錄製時呼叫，之後寫入的事件從頭開始)
+    /// # 返回值
+    /// - Result<(), String>
+    pub fn truncate(&mut self) -> Result<(), String> {
+        self.writer.flush().map_err(|error| error.to_string())?;
+        self.writer.get_ref().set_len(0).map_err(|error| error.to_string())
+    }
+
+    /// 寫入一個事件 (每行寫完就會寫入磁碟)
+    /// # 參數
+    /// - `event` - &StoredEvent
+    /// # 返回值
+    /// - Result<(), String>
+    pub fn append(&mut self, event: &StoredEvent) -> Result<(), String> {
+        serde_json::to_writer(&mut self.writer, event).map_err(|error| error.to_string())?;
+        self.writer.write_all(b"\n").map_err(|error| error.to_string())
+    }
+}
+
+/// 以事件記錄檔 (每行一個 JSON 事件) 儲存錄製內容，用於復原當掉前的錄製
+pub struct LogStorage {
+    path: String,
+}
+
+impl LogStorage {
+
+    /// 建立指定路徑的記錄檔儲存
+    /// # 參數
+    /// - `path` - &str 檔案路徑
+    pub fn new(path: &str) -> Self { LogStorage { path: path.to_string() } }
+}
+
+impl StorageBackend for LogStorage {
+
+    fn save(&self, events: &[StoredEvent], _started_at: Option<SystemTime>) -> Result<(), String> {
+
+        let mut log = EventLog { writer: LineWriter::new(File::create(&self.path).map_err(|error| error.to_string())?) };
+        for event in events { log.append(event)?; }
+
+        Ok(())
+    }
+
+    /// 讀取記錄檔 (最後一行寫到一半時略過；錄製開始時間以第一個事件的時間代替)
+    fn load(&self) -> Result<(Vec<StoredEvent>, SystemTime), String> {
+
+        let text = fs::read_to_string(&self.path).map_err(|error| error.to_string())?;
+        let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        let mut events = Vec::with_capacity(lines.len());
+
+        for (index, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<StoredEvent>(line) {
+                Ok(event) => events.push(event),
+                Err(_) if index + 1 == lines.len() => break,
+                Err(error) => return Err(format!("第 {} 行格式不正確: {}", index + 1, error)),
+            }
+        }
+
+        let started_at = events.first().map(|event| event.time).ok_or("記錄檔沒有事件")?;
+        Ok((events, started_at))
+    }
+}
+
+/// 將錄製事件寫入檔案
+/// - 版本 2 起為二進位格式，事件時間以「與上一個事件的間隔」用 varint 編碼，大幅縮小連續快速事件的檔案大小
+/// - 版本 4 起以奈秒記錄系統時間與單調時間 (elapsed) 的間隔，讀回的事件與寫入時完全相同 (之前的版本只有毫秒，且不保留單調時間)
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// - `events` - &[StoredEvent] 錄製的事件
+/// - `started_at` - Option<SystemTime> 錄製開始的時間 (沒有時以第一個事件為準)
+/// - `info` - &RecordInfo 附加資訊
+/// # 返回值
+/// - Result<(), String>
+pub fn write_record_file(path: &str, events: &[StoredEvent], started_at: Option<SystemTime>, info: &RecordInfo) -> Result<(), String> {
+    let bytes = encode_record(events, started_at, info)?;
+    fs::write(path, bytes).map_err(|error| error.to_string())
+}
+
+/// 從檔案讀取錄製事件 (事件時間以讀取當下的時間為基準重建)
+/// # 參數
+/// - `path` - &str 檔案路徑
+/// # 返回值
+/// - Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> - 錄製的事件、重建後的錄製開始時間與附加資訊
+pub fn read_record_file(path: &str) -> Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> {
+    let bytes = fs::read(path).map_err(|error| error.to_string())?;
+    decode_record(&bytes, SystemTime::now())
+}
+
+/// 將錄製事件編碼成二進位格式
+/// # 參數
+/// - `events` - &[StoredEvent] 錄製的事件
+/// - `started_at` - Option<SystemTime> 錄製開始的時間 (沒有時以第一個事件為準)
+/// - `info` - &RecordInfo 附加資訊
+/// # 返回值
+/// - Result<Vec<u8>, String>
+pub fn encode_record(events: &[StoredEvent], started_at: Option<SystemTime>, info: &RecordInfo) -> Result<Vec<u8>, String> {
+
+    let mut bytes = Vec::with_capacity(16 + events.len() * 4);
+
+    bytes.extend_from_slice(BINARY_MAGIC);
+    bytes.push(RECORD_FILE_VERSION as u8);
+    let metadata = RecordMetadata {
+        disabled: events.iter().enumerate().filter(|(_, event)| !event.enabled).map(|(index, _)| index).collect(),
+        double_clicks: events.iter().enumerate().filter(|(_, event)| event.double_click).map(|(index, _)| index).collect(),
+        labels: events.iter().enumerate().filter(|(_, event)| !event.label.is_empty()).map(|(index, event)| (index, event.label.clone())).collect(),
+        texts: events.iter().enumerate().filter_map(|(index, event)| Some((index, event.text.clone()?))).collect(),
+        tags: info.tags.clone(),
+        name: info.name.clone(),
+        description: info.description.clone(),
+        lock_state: info.lock_state,
+        calls: info.calls.clone(),
+        branches: info.branches.clone(),
+        session_id: info.session_id.clone(),
+        coordinate_mode: info.coordinate_mode,
+    };
+
+    write_json(&mut bytes, &metadata)?;
+    write_varint(&mut bytes, events.len() as u64);
+
+    let mut index = 0;
+    let mut previous = (started_at.or_else(|| events.first().map(|event| event.time)).unwrap_or(SystemTime::UNIX_EPOCH), Duration::ZERO);
+
+    while index < events.len() {
+
+        let event = &events[index];
+        write_timing(&mut bytes, event, &mut previous);
+
+        if let Some(key) = tap_at(events, index) {
+            bytes.push(TAG_KEY_TAP);
+            write_json(&mut bytes, &key)?;
+            write_timing(&mut bytes, &events[index + 1], &mut previous);
+            index += 2;
+            continue;
+        }
+
+        write_event_type(&mut bytes, &event.event_type)?;
+        index += 1;
+    }
+
+    Ok(bytes)
+}
+
+/// 解碼錄製檔案內容 (支援版本 1 的 JSON 與版本 2 起的二進位格式；內容不正確時返回錯誤，不會 panic)
+/// # 參數
+/// - `bytes` - &[u8] 檔案內容
+/// - `base_time` - SystemTime 重建事件時間的基準 (錄製開始時間)
+/// # 返回值
+/// - Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> - 錄製的事件、錄製開始時間與附加資訊 (版本 1 沒有附加資訊)
+pub fn decode_record(bytes: &[u8], base_time: SystemTime) -> Result<(Vec<StoredEvent>, SystemTime, RecordInfo), String> {
+
+    if !bytes.starts_with(BINARY_MAGIC) {
+        let (events, started_at) = decode_json_record(bytes, base_time)?;
+        return Ok((events, started_at, RecordInfo::default()));
+    }
+
+    let mut cursor = BINARY_MAGIC.len();
+    let version = *bytes.get(cursor).ok_or("檔案內容不完整")? as u32;
+    cursor += 1;
+
+    if version > RECORD_FILE_VERSION { return Err(format!("不支援的檔案版本: {}", version)); }
+
+    let metadata: RecordMetadata = read_json(bytes, &mut cursor)?;
+    let count = read_varint(bytes, &mut cursor)? as usize;
+
+    // 每個事件至少佔 2 bytes，先檢查數量避免損壞的檔案要求過大的記憶體
+    if count > bytes.len() / 2 { return Err(format!("事件數量不正確: {}", count)); }
+
+    let mut events = Vec::with_capacity(count);
+    let mut previous = (base_time, Duration::ZERO);
+
+    // 數量是還原後的事件數量，合併的按鍵一筆會還原成兩個事件
+    while events.len() < count {
+
+        let (time, elapsed) = read_timing(bytes, &mut cursor, version, &mut previous)?;
+
+        // 合併的按鍵還原成按下 / 放開兩個事件 (版本 3 起才有)
+        if version >= 3 && bytes.get(cursor) == Some(&TAG_KEY_TAP) {
+            cursor += 1;
+            let key: Key = read_json(bytes, &mut cursor)?;
+            events.push(StoredEvent { elapsed, ..StoredEvent::new(time, EventType::KeyPress(key)) });
+            let (time, elapsed) = read_timing(bytes, &mut cursor, version, &mut previous)?;
+            events.push(StoredEvent { elapsed, ..StoredEvent::new(time, EventType::KeyRelease(key)) });
+            continue;
+        }
+
+        let event_type = read_event_type(bytes, &mut cursor)?;
+        events.push(StoredEvent { elapsed, ..StoredEvent::new(time, event_type) });
+    }
+
+    for index in metadata.disabled {
+        if let Some(event) = events.get_mut(index) { event.enabled = false; }
+    }
+
+    for index in metadata.double_clicks {
+        if let Some(event) = events.get_mut(index) { event.double_click = true; }
+    }
+
+    for (index, label) in metadata.labels {
+        if let Some(event) = events.get_mut(index) { event.label = label; }
+    }
+
+    for (index, text) in metadata.texts {
+        if let Some(event) = events.get_mut(index) { event.text = Some(text); }
+    }
+
+    Ok((events, base_time, RecordInfo { tags: metadata.tags, name: metadata.name, description: metadata.description, lock_state: metadata.lock_state, calls: metadata.calls, branches: metadata.branches, session_id: metadata.session_id, coordinate_mode: metadata.coordinate_mode }))
+}
+
+/// 解碼版本 1 的 JSON 錄製檔案
+/// # 參數
+/// - `bytes` - &[u8] 檔案內容
+/// - `base_time` - SystemTime 重建事件時間的基準
+/// # 返回值
+/// - Result<(Vec<StoredEvent>, SystemTime), String>
+fn decode_json_record(bytes: &[u8], base_time: SystemTime) -> Result<(Vec<StoredEvent>, SystemTime), String> {
+
+    let file: RecordFile = serde_json::from_slice(bytes).map_err(|error| error.to_string())?;
+
+    if file.version > 1 { return Err(format!("不支援的檔案版本: {}", file.version)); }
+
+    let events = file.events.into_iter().map(|event| {
+        Ok(StoredEvent::new(offset_time(base_time, event.offset_ms)?, event.event_type))
+    }).collect::<Result<Vec<_>, String>>()?;
+
+    Ok((events, base_time))
+}
+
+/// 寫入事件的時間 (版本 4)
+/// - 系統時間：與上一個事件的差 (奈秒，zigzag varint，錄製期間系統時間往回調整時為負數)
+/// - 單調時間：0 表示沒有，否則為與上一個單調時間的差 (奈秒) zigzag 後加 1
+/// # 參數
+/// - `bytes` - &mut Vec<u8>
+/// - `event` - &StoredEvent
+/// - `previous` - &mut (SystemTime, Duration) 上一個事件的系統時間與單調時間 (寫入後更新)
+fn write_timing(bytes: &mut Vec<u8>, event: &StoredEvent, previous: &mut (SystemTime, Duration)) {
+
+    let delta = match event.time.duration_since(previous.0) {
+        Ok(forward) => i64::try_from(forward.as_nanos()).unwrap_or(i64::MAX),
+        Err(backward) => i64::try_from(backward.duration().as_nanos()).map_or(i64::MIN, |nanos| -nanos),
+    };
+    write_signed_varint(bytes, delta);
+    previous.0 = event.time;
+
+    let Some(elapsed) = event.elapsed else { write_varint(bytes, 0); return; };
+    let delta = i64::try_from(elapsed.as_nanos() as i128 - previous.1.as_nanos() as i128).unwrap_or(i64::MAX);
+    write_varint(bytes, zigzag(delta).saturating_add(1));
+    previous.1 = elapsed;
+}
+
+/// 讀取事件的時間 (版本 4 起為奈秒的系統時間與單調時間；之前的版本只有與上一個事件的間隔毫秒數，沒有單調時間)
+/// # 參數
+/// - `bytes` - &[u8]
+/// - `cursor` - &mut usize 目前讀取的位置
+/// - `version` - u32 檔案版本
+/// - `previous` - &mut (SystemTime, Duration) 上一個事件的系統時間與單調時間 (讀取後更新)
+/// # 返回值
+/// - Result<(SystemTime, Option<Duration>), String> - 事件的系統時間與單調時間
+fn read_timing(bytes: &[u8], cursor: &mut usize, version: u32, previous: &mut (SystemTime, Duration)) -> Result<(SystemTime, Option<Duration>), String> {
+
+    if version < 4 {
+        previous.0 = offset_time(previous.0, read_varint(bytes, cursor)?)?;
+        return Ok((previous.0, None));
+    }
+
+    let delta = read_signed_varint(bytes, cursor)?;
+    let magnitude = Duration::from_nanos(delta.unsigned_abs());
+    let time = if delta < 0 { previous.0.checked_sub(magnitude) } else { previous.0.checked_add(magnitude) };
+    previous.0 = time.ok_or_else(|| format!("事件時間超出範圍: {} ns", delta))?;
+
+    let elapsed = match read_varint(bytes, cursor)? {
+        0 => None,
+        code => {
+            let nanos = previous.1.as_nanos() as i128 + unzigzag(code - 1) as i128;
+            previous.1 = u64::try_from(nanos).map(Duration::from_nanos).map_err(|_| format!("單調時間超出範圍: {} ns", nanos))?;
+            Some(previous.1)
+        }
+    };
+
+    Ok((previous.0, elapsed))
+}
+
+/// 計算基準時間加上毫秒數後的時間 (檔案內容損壞造成溢位時返回錯誤，而不是 panic)
+/// # 參數
+/// - `time` - SystemTime 基準時間
+/// - `offset_ms` - u64 毫秒數
+/// # 返回值
+/// - Result<SystemTime, String>
+fn offset_time(time: SystemTime, offset_ms: u64) -> Result<SystemTime, String> {
+    time.checked_add(Duration::from_millis(offset_ms)).ok_or_else(|| format!("事件時間超出範圍: {} ms", offset_ms))
+}
+
+/// 設定儲存時是否把快速按下再放開的同一個按鍵合併成一筆 (縮小檔案，載入時會還原)
+/// # 參數
+/// - `enabled` - bool 是否合併 (預設為 false)
+/// - `threshold_ms` - u64 按住時間不超過此值才合併
+/// # 返回值
+/// - bool - 返回當前設定
+pub fn update_coalesce_taps(enabled: bool, threshold_ms: u64) -> bool {
+    TAP_THRESHOLD_MS.store(threshold_ms, Ordering::SeqCst);
+    COALESCE_TAPS.store(enabled, Ordering::SeqCst);
+    COALESCE_TAPS.load(Ordering::SeqCst)
+}
+
+/// 取得儲存時合併按鍵的設定
+/// # 返回值
+/// - (bool, u64) - (是否合併, 按住時間的上限毫秒數)
+pub fn coalesce_taps() -> (bool, u64) {
+    (COALESCE_TAPS.load(Ordering::SeqCst), TAP_THRESHOLD_MS.load(Ordering::SeqCst))
+}
+
+/// 檢查指定位置是否為可合併的按鍵 (按下後緊接著放開同一個按鍵；中間夾著其它事件的組合鍵不合併)
+/// # 參數
+/// - `events` - &[StoredEvent]
+/// - `index` - usize
+/// # 返回值
+/// - Option<Key> - 可合併時返回按鍵
+fn tap_at(events: &[StoredEvent], index: usize) -> Option<Key> {
+
+    if !COALESCE_TAPS.load(Ordering::SeqCst) { return None; }
+
+    let (press, release) = (events.get(index)?, events.get(index + 1)?);
+    let hold_ms = release.gap_since(press).as_millis() as u64;
+
+    match (press.event_type, release.event_type) {
+        (EventType::KeyPress(key), EventType::KeyRelease(released)) if key == released && hold_ms <= TAP_THRESHOLD_MS.load(Ordering::SeqCst) => Some(key),
+        _ => None,
+    }
+}
+
+// MARK: JSON Schema
+/// rdev::Key 的 JsonSchema (rdev 的型別沒有 JsonSchema，依 serde 的輸出格式描述)
+struct KeySchema;
+
+impl JsonSchema for KeySchema {
+
+    fn schema_name() -> String { "Key".to_string() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        schema_of(json!({
+            "description": "rdev::Key (例如 \"KeyA\"、\"ShiftLeft\"；未知按鍵為 {\"Unknown\": code})",
+            "oneOf": [
+                { "type": "string" },
+                unknown_code(),
+            ],
+        }))
+    }
+}
+
+/// rdev::Button 的 JsonSchema
+struct ButtonSchema;
+
+impl JsonSchema for ButtonSchema {
+
+    fn schema_name() -> String { "Button".to_string() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        schema_of(json!({
+            "description": "rdev::Button (\"Left\" / \"Right\" / \"Middle\"；其它為 {\"Unknown\": code})",
+            "oneOf": [
+                { "enum": ["Left", "Right", "Middle"] },
+                unknown_code(),
+            ],
+        }))
+    }
+}
+
+/// rdev::EventType 的 JsonSchema
+pub(crate) struct EventTypeSchema;
+
+impl JsonSchema for EventTypeSchema {
+
+    fn schema_name() -> String { "EventType".to_string() }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+
+        let key = json!(generator.subschema_for::<KeySchema>());
+        let button = json!(generator.subschema_for::<ButtonSchema>());
+        let tagged = |name: &str, value: Value| json!({ "type": "object", "properties": { name: value }, "required": [name], "additionalProperties": false });
+
+        schema_of(json!({
+            "description": "rdev::EventType",
+            "oneOf": [
+                tagged("KeyPress", key.clone()),
+                tagged("KeyRelease", key),
+                tagged("ButtonPress", button.clone()),
+                tagged("ButtonRelease", button),
+                tagged("MouseMove", json!({ "type": "object", "properties": { "x": { "type": "number" }, "y": { "type": "number" } }, "required": ["x", "y"] })),
+                tagged("Wheel", json!({ "type": "object", "properties": { "delta_x": { "type": "integer" }, "delta_y": { "type": "integer" } }, "required": ["delta_x", "delta_y"] })),
+            ],
+        }))
+    }
+}
+
+/// 未知按鍵 / 滑鼠按鍵的格式 ({"Unknown": code})
+/// # 返回值
+/// - Value
+fn unknown_code() -> Value {
+    json!({ "type": "object", "properties": { "Unknown": { "type": "integer" } }, "required": ["Unknown"], "additionalProperties": false })
+}
+
+/// 將 JSON 轉成 Schema
+/// # 參數
+/// - `value` - Value
+/// # 返回值
+/// - Schema
+fn schema_of(value: Value) -> Schema {
+    serde_json::from_value(value).unwrap_or(Schema::Bool(true))
+}
+
+/// 錄製格式的 JSON Schema (依 RECORD_FILE_VERSION 標示版本，由型別的 JsonSchema 產生)
+/// - `definitions.RecordFile` 為版本 1 的 JSON 檔案；版本 2 起的二進位格式說明在 `description`
+/// - `definitions.StoredEvent` 為指令返回的事件 (例如 `split_record`)，`definitions.RecordMetadata` 為二進位格式的附加資訊
+/// # 返回值
+/// - String
+pub fn record_schema() -> String {
+
+    let mut generator = SchemaGenerator::new(SchemaSettings::draft07());
+    generator.subschema_for::<StoredEvent>();
+    generator.subschema_for::<RecordMetadata>();
+
+    let mut schema = RootSchema {
+        meta_schema: generator.settings().meta_schema.clone(),
+        schema: generator.subschema_for::<RecordFile>().into_object(),
+        definitions: generator.take_definitions(),
+    };
+
+    let metadata = schema.schema.metadata();
+    metadata.id = Some(format!("tauri-keyboard-recorder/record-v{}", RECORD_FILE_VERSION));
+    metadata.title = Some("Tauri Keyboard Recorder 錄製格式".to_string());
+    metadata.description = Some(format!(
+        "版本 1 為 JSON (RecordFile)。版本 {} 為二進位：\"TKRB\" + 版本 (1 byte) + 附加資訊 (RecordMetadata，varint 長度 + JSON) + 事件數量 (varint) + 每個事件 [時間 + 類型 (1 byte) + 內容]。時間在版本 4 為與上一個事件的系統時間差 (奈秒，zigzag varint) + 單調時間 (varint：0 為沒有，否則為與上一個單調時間的差 (奈秒) zigzag 後加 1)，版本 2-3 為與上一個事件的間隔毫秒 (varint)。類型 0-3 (按鍵 / 滑鼠按鍵) 的內容為 varint 長度 + JSON，4 (MouseMove) 為兩個 f64 (little endian)，5 (Wheel) 為兩個 zigzag varint，6 (版本 3 起，合併的按下 / 放開) 為按鍵 JSON + 放開的時間 (版本 3 為按住的毫秒數)。",
+        RECORD_FILE_VERSION
+    ));
+
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+// MARK: 二進位編碼
+/// 寫入 varint (每個 byte 使用 7 bits，最高位元表示後面還有資料)
+/// # 參數
+/// - `bytes` - &mut Vec<u8>
+/// - `value` - u64
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 { bytes.push(byte); return; }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// 讀取 varint
+/// # 參數
+/// - `bytes` - &[u8]
+/// - `cursor` - &mut usize 目前讀取的位置
+/// # 返回值
+/// - Result<u64, String>
+fn read_varint(bytes: &[u8], cursor: &mut usize) -> Result<u64, String> {
+
+    let mut value: u64 = 0;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*cursor).ok_or("檔案內容不完整")?;
+        *cursor += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 { return Ok(value); }
+    }
+
+    Err("varint 長度不正確".to_string())
+}
+
+/// 寫入有號整數 (zigzag 編碼後以 varint 寫入)
+/// # 參數
+/// - `bytes` - &mut Vec<u8>
+/// - `value` - i64
+fn write_signed_varint(bytes: &mut Vec<u8>, value: i64) {
+    write_varint(bytes, zigzag(value));
+}
+
+/// 讀取有號整數
+/// # 參數
+/// - `bytes` - &[u8]
+/// - `cursor` - &mut usize 目前讀取的位置
+/// # 返回值
+/// - Result<i64, String>
+fn read_signed_varint(bytes: &[u8], cursor: &mut usize) -> Result<i64, String> {
+    Ok(unzigzag(read_varint(bytes, cursor)?))
+}
+
+/// zigzag 編碼 (絕對值小的負數也只佔少量 bytes)
+/// # 參數
+/// - `value` - i64
+/// # 返回值
+/// - u64
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// zigzag 解碼
+/// # 參數
+/// - `value` - u64
+/// # 返回值
+/// - i64
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// 寫入 f64 (little endian)
+/// # 參數
+/// - `bytes` - &mut Vec<u8>
+/// - `value` - f64
+fn write_f64(bytes: &mut Vec<u8>, value: f64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+/// 讀取 f64 (little endian)
+/// # 參數
+/// - `bytes` - &[u8]
+/// - `cursor` - &mut usize 目前讀取的位置
+/// # 返回值
+/// - Result<f64, String>
+fn read_f64(bytes: &[u8], cursor: &mut usize) -> Result<f64, String> {
+
+    let end = cursor.checked_add(8).ok_or("檔案內容不完整")?;
+    let slice = bytes.get(*cursor..end).ok_or("檔案內容不完整")?;
+    *cursor = end;
+
+    Ok(f64::from_le_bytes(slice.try_into().map_err(|_| "檔案內容不完整".to_string())?))
+}
+
+/// 寫入 JSON 資料 (前面加上 varint 長度)
+/// # 參數
+/// - `bytes` - &mut Vec<u8>
+/// - `value` - &T
+/// # 返回值
+/// - Result<(), String>
+fn write_json<T: Serialize>(bytes: &mut Vec<u8>, value: &T) -> Result<(), String> {
+
+    let json = serde_json::to_vec(value).map_err(|error| error.to_string())?;
+    write_varint(bytes, json.len() as u64);
+    bytes.extend_from_slice(&json);
+
+    Ok(())
+}
+
+/// 讀取 JSON 資料
+/// # 參數
+/// - `bytes` - &[u8]
+/// - `cursor` - &mut usize 目前讀取的位置
+/// # 返回值
+/// - Result<T, String>
+fn read_json<T: DeserializeOwned>(bytes: &[u8], cursor: &mut usize) -> Result<T, String> {
+
+    let length = read_varint(bytes, cursor)? as usize;
+    let end = cursor.checked_add(length).filter(|end| *end <= bytes.len()).ok_or("檔案內容不完整")?;
+    let value = serde_json::from_slice(&bytes[*cursor..end]).map_err(|error| error.to_string())?;
+    *cursor = end;
+
+    Ok(value)
+}
+
+/// 寫入事件類型 (1 byte 類型 + 內容)
+/// # 參數
+/// - `bytes` - &mut Vec<u8>
+/// - `event_type` - &EventType
+/// # 返回值
+/// - Result<(), String>
+fn write_event_type(bytes: &mut Vec<u8>, event_type: &EventType) -> Result<(), String> {
+
+    match *event_type {
+        EventType::KeyPress(key) => { bytes.push(TAG_KEY_PRESS); write_json(bytes, &key) }
+        EventType::KeyRelease(key) => { bytes.push(TAG_KEY_RELEASE); write_json(bytes, &key) }
+        EventType::ButtonPress(button) => { bytes.push(TAG_BUTTON_PRESS); write_json(bytes, &button) }
+        EventType::ButtonRelease(button) => { bytes.push(TAG_BUTTON_RELEASE); write_json(bytes, &button) }
+        EventType::MouseMove { x, y } => {
+            bytes.push(TAG_MOUSE_MOVE);
+            write_f64(bytes, x);
+            write_f64(bytes, y);
+            Ok(())
+        }
+        EventType::Wheel { delta_x, delta_y } => {
+            bytes.push(TAG_WHEEL);
+            write_signed_varint(bytes, delta_x);
+            write_signed_varint(bytes, delta_y);
+            Ok(())
+        }
+    }
+}
+
+/// 讀取事件類型
+/// # 參數
+/// - `bytes` - &[u8]
+/// - `cursor` - &mut usize 目前讀取的位置
+/// # 返回值
+/// - Result<EventType, String>
+fn read_event_type(bytes: &[u8], cursor: &mut usize) -> Result<EventType, String> {
+
+    let tag = *bytes.get(*cursor).ok_or("檔案內容不完整")?;
+    *cursor += 1;
+
+    match tag {
+        TAG_KEY_PRESS => Ok(EventType::KeyPress(read_json(bytes, cursor)?)),
+        TAG_KEY_RELEASE => Ok(EventType::KeyRelease(read_json(bytes, cursor)?)),
+        TAG_BUTTON_PRESS => Ok(EventType::ButtonPress(read_json(bytes, cursor)?)),
+        TAG_BUTTON_RELEASE => Ok(EventType::ButtonRelease(read_json(bytes, cursor)?)),
+        TAG_MOUSE_MOVE => Ok(EventType::MouseMove { x: read_f64(bytes, cursor)?, y: read_f64(bytes, cursor)? }),
+        TAG_WHEEL => Ok(EventType::Wheel { delta_x: read_signed_varint(bytes, cursor)?, delta_y: read_signed_varint(bytes, cursor)? }),
+        _ => Err(format!("未知的事件類型: {}", tag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::time::UNIX_EPOCH;
+    use proptest::prelude::*;
+    use rdev::Button;
+    use rdev::EventType::{ButtonPress, ButtonRelease, KeyPress, KeyRelease, MouseMove, Wheel};
+    use super::*;
+
+    fn event_at(ms: u64, event_type: EventType) -> StoredEvent { StoredEvent::new(UNIX_EPOCH + Duration::from_millis(ms), event_type) }
+
+    fn fields(event: &StoredEvent) -> (SystemTime, EventType, bool, String, Option<Duration>, bool, Option<String>) {
+        (event.time, event.event_type, event.enabled, event.label.clone(), event.elapsed, event.double_click, event.text.clone())
+    }
+
+    #[test]
+    fn round_trip_keeps_events_identical() {
+
+        let _serial = crate::serial_test();
+        let started_at = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let at = |nanos: u64, elapsed_nanos: Option<u64>, event_type: EventType| StoredEvent {
+            elapsed: elapsed_nanos.map(Duration::from_nanos),
+            ..StoredEvent::new(started_at + Duration::from_nanos(nanos), event_type)
+        };
+
+        // 包含次毫秒的間隔、錄製期間系統時間往回調整、沒有單調時間的事件與各種旗標
+        let mut events = [
+            at(250_001, Some(250_001), KeyPress(Key::ShiftLeft)),
+            at(250_900, Some(250_900), KeyRelease(Key::ShiftLeft)),
+            at(100_000, Some(1_000_017), MouseMove { x: 12.345_678, y: -0.5 }),
+            at(1_200_000, Some(1_100_003), ButtonPress(Button::Left)),
+            at(1_200_333, Some(1_100_336), ButtonRelease(Button::Left)),
+            at(9_000_000_001, None, Wheel { delta_x: -3, delta_y: 7 }),
+            at(9_000_000_002, Some(900_000_000), KeyPress(Key::Unknown(42))),
+        ];
+        events[2].enabled = false;
+        events[3].label = "點擊".to_string();
+        events[4].double_click = true;
+        events[6].text = Some("é".to_string());
+
+        let (was_enabled, threshold_ms) = coalesce_taps();
+        for is_coalesced in [false, true] {
+            update_coalesce_taps(is_coalesced, 200);
+            let bytes = encode_record(&events, Some(started_at), &RecordInfo::default());
+            let (decoded, _, _) = decode_record(&bytes.unwrap(), started_at).unwrap();
+            assert_eq!(decoded.iter().map(fields).collect::<Vec<_>>(), events.iter().map(fields).collect::<Vec<_>>());
+        }
+        update_coalesce_taps(was_enabled, threshold_ms);
+    }
+
+    #[test]
+    fn schema_is_generated_from_types() {
+
+        let schema: Value = serde_json::from_str(&record_schema()).unwrap();
+        let definitions = &schema["definitions"];
+
+        assert_eq!(schema["$ref"], "#/definitions/RecordFile");
+        for name in ["RecordFile", "RecordFileEvent", "RecordMetadata", "StoredEvent", "EventType", "Key", "Button", "SlotBranch", "LockState", "CoordinateMode"] {
+            assert!(definitions.get(name).is_some(), "缺少 {}", name);
+        }
+        assert_eq!(definitions["StoredEvent"]["properties"]["event_type"]["$ref"], "#/definitions/EventType");
+        assert_eq!(definitions["SlotBranch"]["properties"]["key"]["$ref"], "#/definitions/Key");
+        assert_eq!(definitions["CoordinateMode"]["enum"], json!(["pixels", "normalized"]));
+    }
+
+    #[test]
+    fn coalesced_taps_round_trip() {
+
+        let _serial = crate::serial_test();
+        let (was_enabled, threshold_ms) = coalesce_taps();
+
+        let mut events = [
+            event_at(10, KeyPress(Key::ControlLeft)),
+            event_at(60, KeyRelease(Key::ControlLeft)),
+            event_at(100, KeyPress(Key::KeyA)),
+            event_at(900, KeyRelease(Key::KeyA)),
+            event_at(950, KeyPress(Key::ShiftLeft)),
+            event_at(970, KeyRelease(Key::ShiftLeft)),
+            event_at(1000, MouseMove { x: 1.0, y: 2.0 }),
+        ];
+        events[1].label = "放開 Ctrl".to_string();
+        events[4].enabled = false;
+
+        update_coalesce_taps(false, 200);
+        let plain = encode_record(&events, Some(UNIX_EPOCH), &RecordInfo::default()).unwrap();
+        update_coalesce_taps(true, 200);
+        let coalesced = encode_record(&events, Some(UNIX_EPOCH), &RecordInfo::default());
+        update_coalesce_taps(was_enabled, threshold_ms);
+
+        let coalesced = coalesced.unwrap();
+        assert!(coalesced.len() < plain.len());
+
+        let (decoded, _, _) = decode_record(&coalesced, UNIX_EPOCH).unwrap();
+        assert_eq!(decoded.len(), events.len());
+
+        for (decoded, original) in decoded.iter().zip(&events) {
+            assert_eq!(decoded.event_type, original.event_type);
+            assert_eq!(decoded.time, original.time);
+            assert_eq!(decoded.label, original.label);
+            assert_eq!(decoded.enabled, original.enabled);
+        }
+    }
+
+    /// 解碼後的事件數量不會超過檔案大小 (每個事件至少 2 bytes，合併的按鍵最後一筆可能多還原一個事件)
+    fn assert_bounded(bytes: &[u8]) -> Result<(), TestCaseError> {
+        if let Ok((events, _, _)) = decode_record(bytes, UNIX_EPOCH) { prop_assert!(events.len() <= bytes.len() / 2 + 1); }
+        Ok(())
+    }
+
+    proptest! {
+
+        #[test]
+        fn decode_arbitrary_bytes_does_not_panic(bytes in proptest::collection::vec(any::<u8>(), 0..1024)) {
+            assert_bounded(&bytes)?;
+        }
+
+        #[test]
+        fn decode_arbitrary_binary_body_does_not_panic(version in 0u8..=RECORD_FILE_VERSION as u8 + 1, body in proptest::collection::vec(any::<u8>(), 0..1024)) {
+            let mut bytes = BINARY_MAGIC.to_vec();
+            bytes.push(version);
+            bytes.extend(body);
+            assert_bounded(&bytes)?;
+        }
+
+        #[test]
+        fn decode_corrupted_record_does_not_panic(corruptions in proptest::collection::vec((any::<usize>(), any::<u8>()), 1..8), cut in any::<usize>()) {
+
+            let events = [
+                event_at(10, KeyPress(Key::KeyA)),
+                event_at(30, KeyRelease(Key::KeyA)),
+                event_at(50, MouseMove { x: 10.0, y: 20.0 }),
+                event_at(70, Wheel { delta_x: 0, delta_y: -1 }),
+                event_at(90, ButtonPress(Button::Left)),
+            ];
+            let mut bytes = encode_record(&events, Some(UNIX_EPOCH), &RecordInfo::default()).unwrap();
+            for (position, value) in corruptions {
+                let length = bytes.len();
+                bytes[position % length] = value;
+            }
+            bytes.truncate(cut % (bytes.len() + 1));
+
+            assert_bounded(&bytes)?;
+        }
+    }
+
+    #[test]
+    fn truncated_event_log_keeps_only_the_new_session() {
+
+        let path = std::env::temp_dir().join(format!("stream-{}-{}.log", std::process::id(), SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos()));
+        let path = path.to_str().unwrap();
+
+        let mut log = EventLog::open(path).unwrap();
+        log.append(&event_at(0, KeyPress(Key::KeyA))).unwrap();
+        log.truncate().unwrap();
+        log.append(&event_at(10, KeyPress(Key::KeyB))).unwrap();
+        drop(log);
+
+        let (events, _) = LogStorage::new(path).load().unwrap();
+        let _ = fs::remove_file(path);
+
+        assert_eq!(events.iter().map(|event| event.event_type).collect::<Vec<_>>(), [KeyPress(Key::KeyB)]);
+    }
+}
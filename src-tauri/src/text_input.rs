@@ -0,0 +1,38 @@
+//! 以系統的文字輸入功能一次送出整段文字 (回放時取代逐鍵模擬，Unicode 輸入模式用)
+//! - macOS：以 osascript 呼叫 System Events 的 keystroke (需允許「輔助使用」與「自動化」權限)
+//! - Linux：以 `xdotool type` 送出 (需安裝 xdotool，只支援 X11)
+//! - Windows 與 Wayland：尚未支援，回放時會改回逐鍵模擬
+
+use std::process::Command;
+
+/// 送出一段文字
+/// # 參數
+/// - `text` - &str 文字
+/// # 返回值
+/// - Result<(), String> - 平台不支援或指令執行失敗時返回錯誤
+pub fn type_text(text: &str) -> Result<(), String> {
+
+    let mut command = type_text_command(text).ok_or("此平台不支援文字輸入")?;
+    let status = command.status().map_err(|error| error.to_string())?;
+
+    if status.success() { Ok(()) } else { Err(format!("文字輸入失敗: {}", status)) }
+}
+
+#[cfg(target_os = "macos")]
+fn type_text_command(text: &str) -> Option<Command> {
+    let mut command = Command::new("osascript");
+    command.args(["-e", "on run argv", "-e", "tell application \"System Events\" to keystroke (item 1 of argv)", "-e", "end run", text]);
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn type_text_command(text: &str) -> Option<Command> {
+    let mut command = Command::new("xdotool");
+    command.args(["type", "--clearmodifiers", "--", text]);
+    Some(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn type_text_command(_text: &str) -> Option<Command> {
+    None
+}
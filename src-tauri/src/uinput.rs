@@ -0,0 +1,201 @@
+//! Linux 以 uinput 虛擬裝置送出回放事件 (需啟用 `linux-uinput` feature)
+//! - 需要 /dev/uinput 的寫入權限，例如加入 udev 規則後把使用者加入 input 群組：
+//!   `KERNEL=="uinput", GROUP="input", MODE="0660"` (/etc/udev/rules.d/99-uinput.rules)
+//! - 滑鼠移動以絕對座標送出 (座標軸的範圍為建立裝置時的螢幕大小，不受滑鼠加速影響)；無法取得螢幕大小時滑鼠移動改用 rdev 的 simulate
+//! - 沒有對應 evdev 按鍵的事件 (例如 F13 以上的功能鍵) 也改用 rdev 的 simulate
+
+use std::sync::{Arc, Mutex};
+use lazy_static::lazy_static;
+use evdev::{AbsInfo, AbsoluteAxisType, AttributeSet, EventType as EvEventType, InputEvent, Key as EvKey, RelativeAxisType, UinputAbsSetup};
+use evdev::uinput::{VirtualDevice, VirtualDeviceBuilder};
+use rdev::{display_size, Button, EventType, Key};
+
+use crate::lock_or_recover;
+use crate::media::MEDIA_KEYS;
+
+/// 虛擬裝置與絕對座標軸的範圍
+struct UinputDevice {
+    device: VirtualDevice,
+    display_size: Option<(i32, i32)>, // 沒有絕對座標軸時為 None
+}
+
+// 用於存儲 uinput 虛擬裝置的全局變數 (None 表示使用 rdev 的 simulate)
+lazy_static! {
+    static ref DEVICE: Arc<Mutex<Option<UinputDevice>>> = Arc::new(Mutex::new(None));
+}
+
+/// 開啟 / 關閉 uinput 虛擬裝置
+/// # 參數
+/// - `enabled` - bool
+/// # 返回值
+/// - Result<bool, String> - 返回當前是否使用 uinput，無法建立裝置時返回錯誤
+pub fn set_enabled(enabled: bool) -> Result<bool, String> {
+
+    let mut device = lock_or_recover(&DEVICE);
+
+    if !enabled { *device = None; return Ok(false); }
+    if device.is_some() { return Ok(true); }
+
+    let display_size = display_size().ok().filter(|(width, height)| *width > 0 && *height > 0).map(|(width, height)| (width as i32, height as i32));
+    *device = Some(UinputDevice { device: build_device(display_size).map_err(|error| error.to_string())?, display_size });
+    Ok(true)
+}
+
+/// 是否正在使用 uinput 虛擬裝置
+/// # 返回值
+/// - bool
+pub fn is_enabled() -> bool { lock_or_recover(&DEVICE).is_some() }
+
+/// 是否可以用 uinput 送出滑鼠移動 (建立裝置時取得了螢幕大小)
+/// # 返回值
+/// - bool
+pub fn supports_mouse_move() -> bool { lock_or_recover(&DEVICE).as_ref().is_some_and(|device| device.display_size.is_some()) }
+
+/// 以 uinput 送出事件
+/// # 參數
+/// - `event_type` - &EventType
+/// # 返回值
+/// - Option<Result<(), String>> - 沒有開啟 uinput 或沒有對應的 evdev 事件時返回 None (改用 rdev 的 simulate)
+pub fn emit(event_type: &EventType) -> Option<Result<(), String>> {
+
+    let mut device = lock_or_recover(&DEVICE);
+    let device = device.as_mut()?;
+
+    let events = match *event_type {
+        EventType::KeyPress(key) => key_events(key, 1),
+        EventType::KeyRelease(key) => key_events(key, 0),
+        EventType::ButtonPress(button) => button_events(button, 1),
+        EventType::ButtonRelease(button) => button_events(button, 0),
+        EventType::MouseMove { x, y } => device.display_size.map(|(width, height)| vec![
+            InputEvent::new(EvEventType::ABSOLUTE, AbsoluteAxisType::ABS_X.0, (x.round() as i32).clamp(0, width - 1)),
+            InputEvent::new(EvEventType::ABSOLUTE, AbsoluteAxisType::ABS_Y.0, (y.round() as i32).clamp(0, height - 1)),
+        ]),
+        EventType::Wheel { delta_x, delta_y } => Some(vec![
+            InputEvent::new(EvEventType::RELATIVE, RelativeAxisType::REL_HWHEEL.0, delta_x as i32),
+            InputEvent::new(EvEventType::RELATIVE, RelativeAxisType::REL_WHEEL.0, delta_y as i32),
+        ]),
+    };
+
+    events.map(|events| device.device.emit(&events).map_err(|error| error.to_string()))
+}
+
+/// 建立虛擬裝置 (鍵盤 + 滑鼠)
+/// # 參數
+/// - `display_size` - Option<(i32, i32)> 絕對座標軸的範圍 (None 表示不建立絕對座標軸)
+/// # 返回值
+/// - std::io::Result<VirtualDevice>
+fn build_device(display_size: Option<(i32, i32)>) -> std::io::Result<VirtualDevice> {
+
+    let mut keys = AttributeSet::<EvKey>::new();
+    for key in ALL_KEYS.iter().filter_map(|key| map_key(*key)) { keys.insert(key); }
+    for key in MEDIA_KEYS.iter().filter_map(|(_, code)| map_key(Key::Unknown(*code))) { keys.insert(key); }
+    for button in [EvKey::BTN_LEFT, EvKey::BTN_RIGHT, EvKey::BTN_MIDDLE] { keys.insert(button); }
+
+    let mut axes = AttributeSet::<RelativeAxisType>::new();
+    for axis in [RelativeAxisType::REL_WHEEL, RelativeAxisType::REL_HWHEEL] { axes.insert(axis); }
+
+    let mut builder = VirtualDeviceBuilder::new()?
+        .name("Tauri Keyboard Recorder")
+        .with_keys(&keys)?
+        .with_relative_axes(&axes)?;
+
+    if let Some((width, height)) = display_size {
+        builder = builder
+            .with_absolute_axis(&UinputAbsSetup::new(AbsoluteAxisType::ABS_X, AbsInfo::new(0, 0, width - 1, 0, 0, 0)))?
+            .with_absolute_axis(&UinputAbsSetup::new(AbsoluteAxisType::ABS_Y, AbsInfo::new(0, 0, height - 1, 0, 0, 0)))?;
+    }
+
+    builder.build()
+}
+
+/// 按鍵事件
+/// # 參數
+/// - `key` - Key
+/// - `value` - i32 1 為按下、0 為放開
+/// # 返回值
+/// - Option<Vec<InputEvent>> - 沒有對應的按鍵時返回 None
+fn key_events(key: Key, value: i32) -> Option<Vec<InputEvent>> {
+    map_key(key).map(|key| vec![InputEvent::new(EvEventType::KEY, key.code(), value)])
+}
+
+/// 滑鼠按鍵事件
+/// # 參數
+/// - `button` - Button
+/// - `value` - i32 1 為按下、0 為放開
+/// # 返回值
+/// - Option<Vec<InputEvent>> - 沒有對應的按鍵時返回 None
+fn button_events(button: Button, value: i32) -> Option<Vec<InputEvent>> {
+
+    let button = match button {
+        Button::Left => EvKey::BTN_LEFT,
+        Button::Right => EvKey::BTN_RIGHT,
+        Button::Middle => EvKey::BTN_MIDDLE,
+        _ => return None,
+    };
+
+    Some(vec![InputEvent::new(EvEventType::KEY, button.code(), value)])
+}
+
+/// 支援以 uinput 送出的按鍵
+const ALL_KEYS: [Key; 104] = [
+    Key::KeyA, Key::KeyB, Key::KeyC, Key::KeyD, Key::KeyE, Key::KeyF, Key::KeyG, Key::KeyH, Key::KeyI, Key::KeyJ, Key::KeyK, Key::KeyL, Key::KeyM,
+    Key::KeyN, Key::KeyO, Key::KeyP, Key::KeyQ, Key::KeyR, Key::KeyS, Key::KeyT, Key::KeyU, Key::KeyV, Key::KeyW, Key::KeyX, Key::KeyY, Key::KeyZ,
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    Key::ShiftLeft, Key::ShiftRight, Key::ControlLeft, Key::ControlRight, Key::Alt, Key::AltGr, Key::MetaLeft, Key::MetaRight,
+    Key::Return, Key::Escape, Key::Backspace, Key::Tab, Key::Space, Key::CapsLock, Key::Delete,
+    Key::UpArrow, Key::DownArrow, Key::LeftArrow, Key::RightArrow, Key::Home, Key::End, Key::PageUp, Key::PageDown,
+    Key::Minus, Key::Equal, Key::LeftBracket, Key::RightBracket, Key::SemiColon, Key::Quote,
+    Key::Comma, Key::Dot, Key::Slash, Key::BackSlash, Key::BackQuote,
+    Key::Insert, Key::PrintScreen, Key::ScrollLock, Key::Pause, Key::NumLock, Key::IntlBackslash,
+    Key::Kp0, Key::Kp1, Key::Kp2, Key::Kp3, Key::Kp4, Key::Kp5, Key::Kp6, Key::Kp7, Key::Kp8, Key::Kp9,
+    Key::KpReturn, Key::KpMinus, Key::KpPlus, Key::KpMultiply, Key::KpDivide, Key::KpDelete,
+];
+
+/// 將 rdev 的按鍵換成 evdev 的按鍵
+/// # 參數
+/// - `key` - Key
+/// # 返回值
+/// - Option<EvKey> - 沒有對應的按鍵時返回 None
+fn map_key(key: Key) -> Option<EvKey> {
+
+    let key = match key {
+        Key::KeyA => EvKey::KEY_A, Key::KeyB => EvKey::KEY_B, Key::KeyC => EvKey::KEY_C, Key::KeyD => EvKey::KEY_D,
+        Key::KeyE => EvKey::KEY_E, Key::KeyF => EvKey::KEY_F, Key::KeyG => EvKey::KEY_G, Key::KeyH => EvKey::KEY_H,
+        Key::KeyI => EvKey::KEY_I, Key::KeyJ => EvKey::KEY_J, Key::KeyK => EvKey::KEY_K, Key::KeyL => EvKey::KEY_L,
+        Key::KeyM => EvKey::KEY_M, Key::KeyN => EvKey::KEY_N, Key::KeyO => EvKey::KEY_O, Key::KeyP => EvKey::KEY_P,
+        Key::KeyQ => EvKey::KEY_Q, Key::KeyR => EvKey::KEY_R, Key::KeyS => EvKey::KEY_S, Key::KeyT => EvKey::KEY_T,
+        Key::KeyU => EvKey::KEY_U, Key::KeyV => EvKey::KEY_V, Key::KeyW => EvKey::KEY_W, Key::KeyX => EvKey::KEY_X,
+        Key::KeyY => EvKey::KEY_Y, Key::KeyZ => EvKey::KEY_Z,
+        Key::Num0 => EvKey::KEY_0, Key::Num1 => EvKey::KEY_1, Key::Num2 => EvKey::KEY_2, Key::Num3 => EvKey::KEY_3,
+        Key::Num4 => EvKey::KEY_4, Key::Num5 => EvKey::KEY_5, Key::Num6 => EvKey::KEY_6, Key::Num7 => EvKey::KEY_7,
+        Key::Num8 => EvKey::KEY_8, Key::Num9 => EvKey::KEY_9,
+        Key::F1 => EvKey::KEY_F1, Key::F2 => EvKey::KEY_F2, Key::F3 => EvKey::KEY_F3, Key::F4 => EvKey::KEY_F4,
+        Key::F5 => EvKey::KEY_F5, Key::F6 => EvKey::KEY_F6, Key::F7 => EvKey::KEY_F7, Key::F8 => EvKey::KEY_F8,
+        Key::F9 => EvKey::KEY_F9, Key::F10 => EvKey::KEY_F10, Key::F11 => EvKey::KEY_F11, Key::F12 => EvKey::KEY_F12,
+        Key::ShiftLeft => EvKey::KEY_LEFTSHIFT, Key::ShiftRight => EvKey::KEY_RIGHTSHIFT,
+        Key::ControlLeft => EvKey::KEY_LEFTCTRL, Key::ControlRight => EvKey::KEY_RIGHTCTRL,
+        Key::Alt => EvKey::KEY_LEFTALT, Key::AltGr => EvKey::KEY_RIGHTALT,
+        Key::MetaLeft => EvKey::KEY_LEFTMETA, Key::MetaRight => EvKey::KEY_RIGHTMETA,
+        Key::Return => EvKey::KEY_ENTER, Key::Escape => EvKey::KEY_ESC, Key::Backspace => EvKey::KEY_BACKSPACE,
+        Key::Tab => EvKey::KEY_TAB, Key::Space => EvKey::KEY_SPACE, Key::CapsLock => EvKey::KEY_CAPSLOCK, Key::Delete => EvKey::KEY_DELETE,
+        Key::UpArrow => EvKey::KEY_UP, Key::DownArrow => EvKey::KEY_DOWN, Key::LeftArrow => EvKey::KEY_LEFT, Key::RightArrow => EvKey::KEY_RIGHT,
+        Key::Home => EvKey::KEY_HOME, Key::End => EvKey::KEY_END, Key::PageUp => EvKey::KEY_PAGEUP, Key::PageDown => EvKey::KEY_PAGEDOWN,
+        Key::Minus => EvKey::KEY_MINUS, Key::Equal => EvKey::KEY_EQUAL, Key::LeftBracket => EvKey::KEY_LEFTBRACE,
+        Key::RightBracket => EvKey::KEY_RIGHTBRACE, Key::SemiColon => EvKey::KEY_SEMICOLON, Key::Quote => EvKey::KEY_APOSTROPHE,
+        Key::Comma => EvKey::KEY_COMMA, Key::Dot => EvKey::KEY_DOT, Key::Slash => EvKey::KEY_SLASH,
+        Key::BackSlash => EvKey::KEY_BACKSLASH, Key::BackQuote => EvKey::KEY_GRAVE,
+        Key::Insert => EvKey::KEY_INSERT, Key::PrintScreen => EvKey::KEY_SYSRQ, Key::ScrollLock => EvKey::KEY_SCROLLLOCK,
+        Key::Pause => EvKey::KEY_PAUSE, Key::NumLock => EvKey::KEY_NUMLOCK, Key::IntlBackslash => EvKey::KEY_102ND,
+        Key::Kp0 => EvKey::KEY_KP0, Key::Kp1 => EvKey::KEY_KP1, Key::Kp2 => EvKey::KEY_KP2, Key::Kp3 => EvKey::KEY_KP3,
+        Key::Kp4 => EvKey::KEY_KP4, Key::Kp5 => EvKey::KEY_KP5, Key::Kp6 => EvKey::KEY_KP6, Key::Kp7 => EvKey::KEY_KP7,
+        Key::Kp8 => EvKey::KEY_KP8, Key::Kp9 => EvKey::KEY_KP9,
+        Key::KpReturn => EvKey::KEY_KPENTER, Key::KpMinus => EvKey::KEY_KPMINUS, Key::KpPlus => EvKey::KEY_KPPLUS,
+        Key::KpMultiply => EvKey::KEY_KPASTERISK, Key::KpDivide => EvKey::KEY_KPSLASH, Key::KpDelete => EvKey::KEY_KPDOT,
+        // 媒體鍵以 X11 按鍵碼記錄，換回 evdev 按鍵碼
+        Key::Unknown(code) if MEDIA_KEYS.iter().any(|(_, media_code)| *media_code == code) => EvKey::new((code - 8) as u16),
+        _ => return None,
+    };
+
+    Some(key)
+}